@@ -0,0 +1,582 @@
+// Generated from the OpenAPI spec by `chores openapi --typescript`. Don't edit by hand
+
+type AddFlashParams = {
+  author?: string | null;
+  contents: string;
+  /**
+   * Epoch seconds to stop showing the flash at, instead of once it's dismissed
+   */
+  expires_at?: number | null;
+  priority?: FlashPriority;
+  /**
+   * Household member to address the flash to
+   */
+  recipient?: string | null;
+  /**
+   * Epoch seconds to start showing the flash at, instead of right away
+   */
+  show_from?: number | null;
+};
+
+type AddFlashResponse = {
+  error?: string | null;
+  id?: number | null;
+  success: boolean;
+};
+
+type ApiChore = {
+  /**
+   * Markdown
+   */
+  description: string;
+  /**
+   * `description` rendered to sanitized HTML
+   */
+  description_html: string;
+  /**
+   * Label to show for the chore, which may change without affecting its history
+   */
+  display_name: string;
+  /**
+   * `expected_completion_time` as RFC 3339 in the configured timezone
+   */
+  expected_completion_at: string;
+  expected_completion_time: number;
+  id: number;
+  /**
+   * Human-readable rendering of the chore's frequency
+   */
+  schedule: string;
+  status: ApiStatus;
+  /**
+   * Stable key of the chore in the config, which its history is stored under
+   */
+  title: string;
+};
+
+/**
+ * Body of a successful `/api/v2` response
+ */
+type ApiData_ChoreInstance = {
+  /**
+   * Identifies a chore instance in `/api/v2` responses
+   */
+  data: {
+    expected_completion_time: number;
+    id: number;
+    title: string;
+  };
+};
+
+/**
+ * Body of a successful `/api/v2` response
+ */
+type ApiData_Flash = {
+  data: {
+    author?: string | null;
+    /**
+     * Markdown
+     */
+    contents: string;
+    /**
+     * `contents` rendered to sanitized HTML
+     */
+    contents_html: string;
+    created_at: number;
+    expires_at?: number | null;
+    id: number;
+    priority: FlashPriority;
+    /**
+     * Household member the flash is addressed to, who is the only one that can dismiss it
+     */
+    recipient?: string | null;
+    show_from?: number | null;
+    /**
+     * When the flash was last edited, if ever
+     */
+    updated_at?: number | null;
+  };
+};
+
+/**
+ * Body of a successful `/api/v2` response
+ */
+type ApiData_FlashHistoryPage = {
+  data: {
+    flashes: FlashHistoryEntry[];
+    /**
+     * Number of flashes across every page
+     */
+    total: number;
+  };
+};
+
+/**
+ * Body of a successful `/api/v2` response
+ */
+type ApiData_FlashId = {
+  data: {
+    id: number;
+  };
+};
+
+/**
+ * Body of a successful `/api/v2` response
+ */
+type ApiData_HashMap_String_StationMetar = {
+  data: { [key: string]: {
+    /**
+     * Whether the station reported CAVOK: at least 10 km of visibility, no cloud below 5000 ft
+     * and no significant weather
+     */
+    cavok: boolean;
+    /**
+     * From the lowest layer up
+     */
+    cloud_layers: CloudLayer[];
+    /**
+     * Degrees Celsius, like `temperature`
+     */
+    dewpoint?: number | null;
+    /**
+     * When the observation was last fetched from the station
+     */
+    fetched_at?: number | null;
+    flight_category?: null | FlightCategory;
+    metar: string;
+    /**
+     * When the station made the observation
+     */
+    observed_at?: number | null;
+    pressure?: number | null;
+    /**
+     * Whether the observation is older than the server's `metar_stale_after`, such as when the
+     * station hasn't been reachable for a while
+     */
+    stale: boolean;
+    /**
+     * The weather in a line, such as "Light rain, 4°C, wind NW 12 gusting 20 kt"
+     */
+    summary: string;
+    temperature?: number | null;
+    /**
+     * How far up can be seen through an obscured sky, which counts as the ceiling. Zero when the
+     * station couldn't tell
+     */
+    vertical_visibility_ft?: number | null;
+    visibility_m?: number | null;
+    visibility_sm?: number | null;
+    weather: Weather[];
+    wind?: null | Wind;
+  } };
+};
+
+/**
+ * Body of a successful `/api/v2` response
+ */
+type ApiData_Option_CatchUpReport = {
+  data: 
+    | null
+    | {
+      caught_up_at: number;
+      chores: CaughtUpChore[];
+      last_update: number;
+    };
+};
+
+/**
+ * Body of a successful `/api/v2` response
+ */
+type ApiData_Vec_ApiChore = {
+  data: {
+    /**
+     * Markdown
+     */
+    description: string;
+    /**
+     * `description` rendered to sanitized HTML
+     */
+    description_html: string;
+    /**
+     * Label to show for the chore, which may change without affecting its history
+     */
+    display_name: string;
+    /**
+     * `expected_completion_time` as RFC 3339 in the configured timezone
+     */
+    expected_completion_at: string;
+    expected_completion_time: number;
+    id: number;
+    /**
+     * Human-readable rendering of the chore's frequency
+     */
+    schedule: string;
+    status: ApiStatus;
+    /**
+     * Stable key of the chore in the config, which its history is stored under
+     */
+    title: string;
+  }[];
+};
+
+/**
+ * Body of a successful `/api/v2` response
+ */
+type ApiData_Vec_ArchivedChore = {
+  data: {
+    instances: ArchivedChoreInstance[];
+    title: string;
+  }[];
+};
+
+/**
+ * Body of a successful `/api/v2` response
+ */
+type ApiData_Vec_Flash = {
+  data: {
+    author?: string | null;
+    /**
+     * Markdown
+     */
+    contents: string;
+    /**
+     * `contents` rendered to sanitized HTML
+     */
+    contents_html: string;
+    created_at: number;
+    expires_at?: number | null;
+    id: number;
+    priority: FlashPriority;
+    /**
+     * Household member the flash is addressed to, who is the only one that can dismiss it
+     */
+    recipient?: string | null;
+    show_from?: number | null;
+    /**
+     * When the flash was last edited, if ever
+     */
+    updated_at?: number | null;
+  }[];
+};
+
+type ApiErrorBody = {
+  /**
+   * One of `bad_request`, `forbidden`, `not_found`, `conflict` or `internal`
+   */
+  code: string;
+  message: string;
+};
+
+/**
+ * Body of a failed `/api/v2` response, sent with a matching HTTP status
+ */
+type ApiErrorResponse = {
+  error: ApiErrorBody;
+};
+
+/**
+ * Changes streamed from `/api/events`, named by `type`
+ */
+type ApiEvent = 
+  | {
+    expected_completion_time: number;
+    id: number;
+    title: string;
+    type: "chore_created";
+  }
+  | {
+    expected_completion_time: number;
+    id: number;
+    title: string;
+    type: "chore_completed";
+  }
+  | {
+    expected_completion_time: number;
+    id: number;
+    status: ApiStatus;
+    title: string;
+    type: "chore_status_changed";
+  }
+  | {
+    flash: Flash;
+    type: "flash_added";
+  }
+  | {
+    id: number;
+    type: "flash_dismissed";
+  }
+  | {
+    flash: Flash;
+    type: "flash_updated";
+  }
+  | {
+    id: number;
+    type: "flash_deleted";
+  }
+  | {
+    stations: { [key: string]: StationMetar };
+    type: "weather_updated";
+  };
+
+type ApiStatus = "upcoming" | "assigned" | "overdue" | "completed" | "missed" | "cancelled";
+
+/**
+ * A chore that was removed from the config, with every instance it had
+ */
+type ArchivedChore = {
+  instances: ArchivedChoreInstance[];
+  title: string;
+};
+
+type ArchivedChoreInstance = {
+  expected_completion_time: number;
+  id: number;
+  status: ApiStatus;
+};
+
+/**
+ * What the scheduler does with occurrences that fully elapsed while the server was down
+ */
+type CatchUpPolicy = "backfill" | "collapse" | "skip";
+
+type CatchUpReport = {
+  caught_up_at: number;
+  chores: CaughtUpChore[];
+  last_update: number;
+};
+
+type CaughtUpChore = {
+  backfilled: number;
+  missed_occurrences: number;
+  policy: CatchUpPolicy;
+  title: string;
+};
+
+/**
+ * Identifies a chore instance in `/api/v2` responses
+ */
+type ChoreInstance = {
+  expected_completion_time: number;
+  id: number;
+  title: string;
+};
+
+type CloudLayer = {
+  /**
+   * Height of the layer above the ground, when known
+   */
+  base_ft?: number | null;
+  /**
+   * `cumulonimbus` or `towering cumulus`, when the station calls them out
+   */
+  cloud_type?: string | null;
+  /**
+   * `few`, `scattered`, `broken`, `overcast` or `unknown`
+   */
+  cover: string;
+};
+
+/**
+ * Identifies the chore instance by `id`, or by `title` and `expected_completion_time` together
+ */
+type CompleteChoreParams = {
+  expected_completion_time?: number | null;
+  id?: number | null;
+  title?: string | null;
+};
+
+type CompleteChoreResponse = {
+  error?: string | null;
+  success: boolean;
+};
+
+type DeleteFlashResponse = {
+  error?: string | null;
+  success: boolean;
+};
+
+type DismissFlashParams = {
+  id: number;
+  /**
+   * Household member dismissing the flash, which must be its recipient if it has one
+   */
+  member?: string | null;
+};
+
+type DismissFlashResponse = {
+  error?: string | null;
+  success: boolean;
+};
+
+type Flash = {
+  author?: string | null;
+  /**
+   * Markdown
+   */
+  contents: string;
+  /**
+   * `contents` rendered to sanitized HTML
+   */
+  contents_html: string;
+  created_at: number;
+  expires_at?: number | null;
+  id: number;
+  priority: FlashPriority;
+  /**
+   * Household member the flash is addressed to, who is the only one that can dismiss it
+   */
+  recipient?: string | null;
+  show_from?: number | null;
+  /**
+   * When the flash was last edited, if ever
+   */
+  updated_at?: number | null;
+};
+
+/**
+ * A flash in `/api/flashes/history`, which includes those already dismissed
+ */
+type FlashHistoryEntry = Flash & {
+  acknowledged: boolean;
+  acknowledged_at?: number | null;
+  acknowledged_by?: string | null;
+};
+
+type FlashHistoryPage = {
+  flashes: FlashHistoryEntry[];
+  /**
+   * Number of flashes across every page
+   */
+  total: number;
+};
+
+type FlashHistoryResponse = {
+  error?: string | null;
+  flashes: FlashHistoryEntry[];
+  success: boolean;
+  total: number;
+};
+
+type FlashId = {
+  id: number;
+};
+
+/**
+ * How prominently a flash is shown, with more urgent flashes first
+ */
+type FlashPriority = "info" | "warning" | "urgent";
+
+/**
+ * The FAA's flight rules categories, from the ceiling and visibility
+ */
+type FlightCategory = "VFR" | "MVFR" | "IFR" | "LIFR";
+
+type GetCatchUpResponse = {
+  report?: null | CatchUpReport;
+};
+
+type GetFlashResponse = {
+  error?: string | null;
+  flashes: Flash[];
+  success: boolean;
+};
+
+type GetMetarsResponse = {
+  stations: { [key: string]: StationMetar };
+};
+
+type ListArchivedChoresResponse = {
+  chores: ArchivedChore[];
+  error?: string | null;
+  success: boolean;
+};
+
+type ListChoresResponse = {
+  chores: ApiChore[];
+  error?: string | null;
+  success: boolean;
+};
+
+type Palette = "bw" | "gray4" | "gray";
+
+type StationMetar = {
+  /**
+   * Whether the station reported CAVOK: at least 10 km of visibility, no cloud below 5000 ft
+   * and no significant weather
+   */
+  cavok: boolean;
+  /**
+   * From the lowest layer up
+   */
+  cloud_layers: CloudLayer[];
+  /**
+   * Degrees Celsius, like `temperature`
+   */
+  dewpoint?: number | null;
+  /**
+   * When the observation was last fetched from the station
+   */
+  fetched_at?: number | null;
+  flight_category?: null | FlightCategory;
+  metar: string;
+  /**
+   * When the station made the observation
+   */
+  observed_at?: number | null;
+  pressure?: number | null;
+  /**
+   * Whether the observation is older than the server's `metar_stale_after`, such as when the
+   * station hasn't been reachable for a while
+   */
+  stale: boolean;
+  /**
+   * The weather in a line, such as "Light rain, 4°C, wind NW 12 gusting 20 kt"
+   */
+  summary: string;
+  temperature?: number | null;
+  /**
+   * How far up can be seen through an obscured sky, which counts as the ceiling. Zero when the
+   * station couldn't tell
+   */
+  vertical_visibility_ft?: number | null;
+  visibility_m?: number | null;
+  visibility_sm?: number | null;
+  weather: Weather[];
+  wind?: null | Wind;
+};
+
+/**
+ * Replaces everything about a flash other than its author
+ */
+type UpdateFlashParams = {
+  contents: string;
+  expires_at?: number | null;
+  priority?: FlashPriority;
+  recipient?: string | null;
+  show_from?: number | null;
+};
+
+type UpdateFlashResponse = {
+  error?: string | null;
+  success: boolean;
+};
+
+type Weather = {
+  conditions: string[];
+  intensity: string;
+};
+
+type Wind = {
+  /**
+   * Degrees true the wind is blowing from, or `None` when it's calm or variable
+   */
+  direction?: number | null;
+  gust_kt?: number | null;
+  speed_kt: number;
+  variable: boolean;
+  /**
+   * The range of directions the wind is varying between, clockwise
+   */
+  varying_from?: number | null;
+  varying_to?: number | null;
+};
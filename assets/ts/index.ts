@@ -1,16 +1,3 @@
-type Chore = {
-  title: string;
-  description: string;
-  expected_completion_time: number;
-  status: string;
-};
-
-type ListChoresResponse = {
-  success: boolean;
-  error?: string;
-  chores: Chore[];
-};
-
 const createCard = (cardType: string, title: string | Node, titleColor: string, contents: Node[]): Node => {
   let cell = document.createElement("div");
   cell.classList.add("cell");
@@ -47,19 +34,19 @@ const createCard = (cardType: string, title: string | Node, titleColor: string,
   return cell;
 }
 
-const fetchChores = async (): Promise<Chore[]> => {
+const fetchChores = async (): Promise<ApiChore[]> => {
   let response = await fetch("/api/chores");
   return (await response.json()).chores;
 }
 
-const createChoreCard = (chore: Chore): Node => {
+const createChoreCard = (chore: ApiChore): Node => {
   let title = document.createElement("h3");
   if (chore.status === "completed" || chore.status === "missed") {
     let struckOut = document.createElement("s");
-    struckOut.textContent = chore.title;
+    struckOut.textContent = chore.display_name;
     title.appendChild(struckOut);
   } else {
-    title.textContent = chore.title;
+    title.textContent = chore.display_name;
   }
 
   let titleColor = "secondary";
@@ -89,8 +76,9 @@ const createChoreCard = (chore: Chore): Node => {
   expectedTime.appendChild(expectedTimeBold);
   contents.push(expectedTime);
 
-  let description = document.createElement("p");
-  description.textContent = chore.description;
+  // sanitized by the server
+  let description = document.createElement("div");
+  description.innerHTML = chore.description_html;
   contents.push(description);
 
   if (chore.status === "assigned" || chore.status === "overdue") {
@@ -104,8 +92,7 @@ const createChoreCard = (chore: Chore): Node => {
 
     completeButton.onclick = async (): Promise<void> => {
       const data = new URLSearchParams();
-      data.append("title", chore.title);
-      data.append("expected_completion_time", chore.expected_completion_time.toString());
+      data.append("id", chore.id.toString());
 
       await fetch("/api/chores/complete", {
         method: "POST",
@@ -136,7 +123,7 @@ const setChores = async (): Promise<void> => {
     return;
   }
 
-  chores.sort((a: Chore, b: Chore): number => {
+  chores.sort((a: ApiChore, b: ApiChore): number => {
     if (a.status == b.status) {
       return a.expected_completion_time - b.expected_completion_time;
     }
@@ -182,27 +169,23 @@ const updateChores = async (): Promise<void> => {
   setTimeout(updateChores, 10000);
 }
 
-type Flash = {
-  id: number;
-  contents: string;
-  created_at: number;
-};
-
-type GetFlashesResponse = {
-  success: boolean;
-  error?: string;
-  flashes: Flash[];
-};
-
 const createFlash = (flash: Flash): Node => {
   let contents = [];
-  let content = document.createElement("h5");
-  content.textContent = flash.contents;
+  // sanitized by the server
+  let content = document.createElement("div");
+  content.classList.add("lead");
+  content.innerHTML = flash.contents_html;
   contents.push(content);
 
   let createTime = document.createElement("p");
   createTime.style.fontSize = "0.8em";
   createTime.textContent = "Created at " + (new Date(flash.created_at * 1000)).toLocaleString();
+  if (flash.author) {
+    createTime.textContent += " by " + flash.author;
+  }
+  if (flash.recipient) {
+    createTime.textContent += " for " + flash.recipient;
+  }
   contents.push(createTime);
 
   let dismiss = document.createElement("button");
@@ -215,6 +198,14 @@ const createFlash = (flash: Flash): Node => {
   dismiss.onclick = async (): Promise<void> => {
       const data = new URLSearchParams();
       data.append("id", flash.id.toString());
+      if (flash.recipient) {
+        // only the recipient can dismiss it
+        const member = prompt("This message is for " + flash.recipient + ". Who's dismissing it?");
+        if (member === null) {
+          return;
+        }
+        data.append("member", member);
+      }
 
       await fetch("/api/flashes/dismiss", {
         method: "POST",
@@ -226,6 +217,12 @@ const createFlash = (flash: Flash): Node => {
 
   contents.push(dismiss);
 
+  if (flash.priority === "urgent") {
+    return createCard("flash", "Urgent", "alert", contents);
+  } else if (flash.priority === "warning") {
+    return createCard("flash", "Warning", "warning", contents);
+  }
+
   return createCard("flash", "Message", "success", contents);
 }
 
@@ -286,23 +283,13 @@ const possiblySendFlash = async (event: KeyboardEvent): Promise<void> => {
   }
 }
 
-type Weather = {
-  conditions: string[];
-  intensity: string;
-};
-
-type StationMetar = {
-  metar: string;
-  pressure?: number;
-  temperature?: number;
-  weather: Weather[];
-};
-
-type GetMetarsResponse = {
-  stations: Map<string, StationMetar>;
-};
-
 const createMetarCard = (station: string, metar: StationMetar): Node => {
+  let summaryText = document.createElement("p");
+  summaryText.textContent = metar.summary;
+  if (metar.flight_category != null) {
+    summaryText.textContent += " (" + metar.flight_category + ")";
+  }
+
   let temperatureText = document.createElement("p");
   temperatureText.textContent = "Temperature: " + metar.temperature + "\u00b0C";
 
@@ -312,7 +299,7 @@ const createMetarCard = (station: string, metar: StationMetar): Node => {
   let metarText = document.createElement("p");
   metarText.textContent = metar.metar;
 
-  return createCard("metar", station, "primary", [temperatureText, pressureText, metarText]);
+  return createCard("metar", station, "primary", [summaryText, temperatureText, pressureText, metarText]);
 }
 
 const setMetars = async (): Promise<void> => {
@@ -381,8 +368,31 @@ const updateMetars = async (): Promise<void> => {
   setTimeout(updateMetars, 10000);
 }
 
+const listenForEvents = (): void => {
+  if (typeof EventSource === "undefined") {
+    return;
+  }
+
+  // the browser reconnects on its own, sending the last event ID so nothing is missed
+  const events = new EventSource("/api/events");
+
+  for (const type of ["chore_created", "chore_completed", "chore_status_changed"]) {
+    events.addEventListener(type, () => { setChores(); });
+  }
+  for (const type of ["flash_added", "flash_dismissed", "flash_updated", "flash_deleted"]) {
+    events.addEventListener(type, () => { setFlashes(); });
+  }
+  events.addEventListener("weather_updated", () => { setMetars(); });
+  events.addEventListener("resync", () => {
+    setChores();
+    setFlashes();
+    setMetars();
+  });
+}
+
 (<any>$(document)).foundation();
 
 updateChores();
 updateFlashes();
 updateMetars();
+listenForEvents();
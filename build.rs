@@ -1,5 +1,121 @@
-// generated by `sqlx migrate build-script`
+use std::env;
+use std::fmt::Write as _;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Directories embedded into the binary, relative to the crate root
+const ASSET_DIRS: &[&str] = &["assets/html", "dist"];
+
+fn collect_files(dir: &Path, files: &mut Vec<PathBuf>) {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(_) => return,
+    };
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            collect_files(&path, files);
+        } else {
+            files.push(path);
+        }
+    }
+}
+
+fn find_tsc(root: &Path) -> Option<PathBuf> {
+    let local = root.join("node_modules").join(".bin").join("tsc");
+    if local.exists() {
+        return Some(local);
+    }
+
+    env::split_paths(&env::var_os("PATH")?)
+        .map(|dir| dir.join("tsc"))
+        .find(|path| path.exists())
+}
+
+/// Compiles `assets/ts` into `out_dir` if the TypeScript compiler is available, returning the
+/// directory of emitted JavaScript. Otherwise the committed `dist/js` output is embedded as is.
+fn compile_typescript(root: &Path, out_dir: &Path) -> Option<PathBuf> {
+    let tsc = find_tsc(root)?;
+
+    let js_dir = out_dir.join("js");
+    let status = Command::new(tsc)
+        .current_dir(root)
+        .arg("--project")
+        .arg("tsconfig.json")
+        .arg("--outDir")
+        .arg(&js_dir)
+        .status();
+
+    match status {
+        Ok(status) if status.success() => Some(js_dir),
+        Ok(status) => {
+            println!(
+                "cargo:warning=tsc failed with {}, embedding the committed dist/js instead",
+                status
+            );
+            None
+        }
+        Err(e) => {
+            println!(
+                "cargo:warning=failed to run tsc ({}), embedding the committed dist/js instead",
+                e
+            );
+            None
+        }
+    }
+}
+
 fn main() {
     // trigger recompilation when a new migration is added
     println!("cargo:rerun-if-changed=migrations");
-}
\ No newline at end of file
+    println!("cargo:rerun-if-changed=assets");
+    println!("cargo:rerun-if-changed=dist");
+    println!("cargo:rerun-if-changed=tsconfig.json");
+
+    let root = PathBuf::from(env::var("CARGO_MANIFEST_DIR").unwrap());
+    let out_dir = PathBuf::from(env::var("OUT_DIR").unwrap());
+
+    let mut assets: Vec<(String, PathBuf)> = Vec::new();
+    for dir in ASSET_DIRS {
+        let mut files = Vec::new();
+        collect_files(&root.join(dir), &mut files);
+
+        for file in files {
+            let key = file
+                .strip_prefix(&root)
+                .unwrap()
+                .to_string_lossy()
+                .replace('\\', "/");
+            assets.push((key, file));
+        }
+    }
+
+    if let Some(js_dir) = compile_typescript(&root, &out_dir) {
+        let mut files = Vec::new();
+        collect_files(&js_dir, &mut files);
+
+        for file in files {
+            let key = format!(
+                "dist/js/{}",
+                file.strip_prefix(&js_dir)
+                    .unwrap()
+                    .to_string_lossy()
+                    .replace('\\', "/")
+            );
+            assets.retain(|(existing, _)| *existing != key);
+            assets.push((key, file));
+        }
+    }
+
+    assets.sort();
+
+    let mut generated = String::from("pub static EMBEDDED_ASSETS: &[(&str, &[u8])] = &[\n");
+    for (key, file) in assets.iter() {
+        writeln!(generated, "    ({:?}, include_bytes!({:?})),", key, file).unwrap();
+    }
+    generated.push_str("];\n");
+
+    fs::write(out_dir.join("assets.rs"), generated).unwrap();
+}
@@ -34,11 +34,11 @@ const createChoreCard = (chore) => {
     let title = document.createElement("h3");
     if (chore.status === "completed" || chore.status === "missed") {
         let struckOut = document.createElement("s");
-        struckOut.textContent = chore.title;
+        struckOut.textContent = chore.display_name;
         title.appendChild(struckOut);
     }
     else {
-        title.textContent = chore.title;
+        title.textContent = chore.display_name;
     }
     let titleColor = "secondary";
     if (chore.status === "completed") {
@@ -67,8 +67,9 @@ const createChoreCard = (chore) => {
     expectedTimeBold.textContent = "Due date: " + expectedDate.toLocaleString();
     expectedTime.appendChild(expectedTimeBold);
     contents.push(expectedTime);
-    let description = document.createElement("p");
-    description.textContent = chore.description;
+    // sanitized by the server
+    let description = document.createElement("div");
+    description.innerHTML = chore.description_html;
     contents.push(description);
     if (chore.status === "assigned" || chore.status === "overdue") {
         let completeButton = document.createElement("button");
@@ -80,8 +81,7 @@ const createChoreCard = (chore) => {
         completeButton.textContent = "Mark Completed";
         completeButton.onclick = async () => {
             const data = new URLSearchParams();
-            data.append("title", chore.title);
-            data.append("expected_completion_time", chore.expected_completion_time.toString());
+            data.append("id", chore.id.toString());
             await fetch("/api/chores/complete", {
                 method: "POST",
                 body: data,
@@ -144,12 +144,20 @@ const updateChores = async () => {
 };
 const createFlash = (flash) => {
     let contents = [];
-    let content = document.createElement("h5");
-    content.textContent = flash.contents;
+    // sanitized by the server
+    let content = document.createElement("div");
+    content.classList.add("lead");
+    content.innerHTML = flash.contents_html;
     contents.push(content);
     let createTime = document.createElement("p");
     createTime.style.fontSize = "0.8em";
     createTime.textContent = "Created at " + (new Date(flash.created_at * 1000)).toLocaleString();
+    if (flash.author) {
+        createTime.textContent += " by " + flash.author;
+    }
+    if (flash.recipient) {
+        createTime.textContent += " for " + flash.recipient;
+    }
     contents.push(createTime);
     let dismiss = document.createElement("button");
     dismiss.type = "button";
@@ -161,6 +169,14 @@ const createFlash = (flash) => {
     dismiss.onclick = async () => {
         const data = new URLSearchParams();
         data.append("id", flash.id.toString());
+        if (flash.recipient) {
+            // only the recipient can dismiss it
+            const member = prompt("This message is for " + flash.recipient + ". Who's dismissing it?");
+            if (member === null) {
+                return;
+            }
+            data.append("member", member);
+        }
         await fetch("/api/flashes/dismiss", {
             method: "POST",
             body: data,
@@ -168,6 +184,12 @@ const createFlash = (flash) => {
         await setFlashes();
     };
     contents.push(dismiss);
+    if (flash.priority === "urgent") {
+        return createCard("flash", "Urgent", "alert", contents);
+    }
+    else if (flash.priority === "warning") {
+        return createCard("flash", "Warning", "warning", contents);
+    }
     return createCard("flash", "Message", "success", contents);
 };
 const setFlashes = async () => {
@@ -212,13 +234,18 @@ const possiblySendFlash = async (event) => {
     }
 };
 const createMetarCard = (station, metar) => {
+    let summaryText = document.createElement("p");
+    summaryText.textContent = metar.summary;
+    if (metar.flight_category != null) {
+        summaryText.textContent += " (" + metar.flight_category + ")";
+    }
     let temperatureText = document.createElement("p");
     temperatureText.textContent = "Temperature: " + metar.temperature + "\u00b0C";
     let pressureText = document.createElement("p");
     pressureText.textContent = "Pressure: " + metar.pressure + " hPa";
     let metarText = document.createElement("p");
     metarText.textContent = metar.metar;
-    return createCard("metar", station, "primary", [temperatureText, pressureText, metarText]);
+    return createCard("metar", station, "primary", [summaryText, temperatureText, pressureText, metarText]);
 };
 const setMetars = async () => {
     let response = await fetch("/api/metars");
@@ -276,7 +303,27 @@ const updateMetars = async () => {
     await setMetars();
     setTimeout(updateMetars, 10000);
 };
+const listenForEvents = () => {
+    if (typeof EventSource === "undefined") {
+        return;
+    }
+    // the browser reconnects on its own, sending the last event ID so nothing is missed
+    const events = new EventSource("/api/events");
+    for (const type of ["chore_created", "chore_completed", "chore_status_changed"]) {
+        events.addEventListener(type, () => { setChores(); });
+    }
+    for (const type of ["flash_added", "flash_dismissed", "flash_updated", "flash_deleted"]) {
+        events.addEventListener(type, () => { setFlashes(); });
+    }
+    events.addEventListener("weather_updated", () => { setMetars(); });
+    events.addEventListener("resync", () => {
+        setChores();
+        setFlashes();
+        setMetars();
+    });
+};
 $(document).foundation();
 updateChores();
 updateFlashes();
 updateMetars();
+listenForEvents();
@@ -0,0 +1,7 @@
+CREATE TABLE updates_utc (
+  update_timestamp TIMESTAMP NOT NULL DEFAULT (STRFTIME('%s', 'now')),
+  PRIMARY KEY (update_timestamp)
+);
+INSERT INTO updates_utc (update_timestamp) SELECT update_timestamp FROM updates;
+DROP TABLE updates;
+ALTER TABLE updates_utc RENAME TO updates;
@@ -0,0 +1,16 @@
+CREATE TABLE chores_with_id (
+  id INTEGER NOT NULL PRIMARY KEY,
+  title TEXT NOT NULL,
+  expected_completion_time TIMESTAMP NOT NULL,
+  status TEXT CHECK(status IN ('assigned', 'completed', 'missed')) NOT NULL DEFAULT 'assigned',
+  created_at TIMESTAMP NOT NULL DEFAULT (STRFTIME('%s', 'now')),
+  overdue_time TIMESTAMP NOT NULL CHECK(overdue_time > expected_completion_time),
+  expiration_time TIMESTAMP NOT NULL,
+  UNIQUE (title, expected_completion_time)
+);
+INSERT INTO chores_with_id (title, expected_completion_time, status, created_at, overdue_time, expiration_time)
+  SELECT title, expected_completion_time, status, created_at, overdue_time, expiration_time
+  FROM chores
+  ORDER BY CAST(expected_completion_time AS INTEGER), title;
+DROP TABLE chores;
+ALTER TABLE chores_with_id RENAME TO chores;
@@ -0,0 +1,53 @@
+-- Time columns were declared TIMESTAMP, which SQLite gives NUMERIC affinity, and were filled with a
+-- mix of integers and the text STRFTIME('%s') returns. Store them all as INTEGER epoch seconds.
+CREATE TABLE chores_integer (
+  id INTEGER NOT NULL PRIMARY KEY,
+  title TEXT NOT NULL,
+  expected_completion_time INTEGER NOT NULL,
+  status TEXT CHECK(status IN ('assigned', 'completed', 'missed')) NOT NULL DEFAULT 'assigned',
+  created_at INTEGER NOT NULL DEFAULT (CAST(STRFTIME('%s', 'now') AS INTEGER)),
+  overdue_time INTEGER NOT NULL CHECK(overdue_time > expected_completion_time),
+  expiration_time INTEGER NOT NULL,
+  UNIQUE (title, expected_completion_time)
+);
+INSERT INTO chores_integer (id, title, expected_completion_time, status, created_at, overdue_time, expiration_time)
+  SELECT
+    id,
+    title,
+    CAST(expected_completion_time AS INTEGER),
+    status,
+    CAST(created_at AS INTEGER),
+    CAST(overdue_time AS INTEGER),
+    CAST(expiration_time AS INTEGER)
+  FROM chores;
+DROP TABLE chores;
+ALTER TABLE chores_integer RENAME TO chores;
+
+-- the list query's time window, and the scheduler's search for assigned chores that expired
+CREATE INDEX chores_expected_completion_time ON chores (expected_completion_time);
+CREATE INDEX chores_status_expiration_time ON chores (status, expiration_time);
+
+CREATE TABLE flashes_integer (
+  id INTEGER NOT NULL PRIMARY KEY,
+  contents TEXT NOT NULL,
+  created_at INTEGER NOT NULL DEFAULT (CAST(STRFTIME('%s', 'now') AS INTEGER)),
+  acknowledged INTEGER NOT NULL DEFAULT 0,
+  acknowledged_at INTEGER
+);
+INSERT INTO flashes_integer (id, contents, created_at, acknowledged, acknowledged_at)
+  SELECT id, contents, CAST(created_at AS INTEGER), acknowledged, CAST(acknowledged_at AS INTEGER)
+  FROM flashes;
+DROP TABLE flashes;
+ALTER TABLE flashes_integer RENAME TO flashes;
+
+-- the unacknowledged flashes, oldest first
+CREATE INDEX flashes_acknowledged_created_at ON flashes (acknowledged, created_at);
+
+CREATE TABLE updates_integer (
+  update_timestamp INTEGER NOT NULL DEFAULT (CAST(STRFTIME('%s', 'now') AS INTEGER)),
+  PRIMARY KEY (update_timestamp)
+);
+INSERT OR IGNORE INTO updates_integer (update_timestamp)
+  SELECT CAST(update_timestamp AS INTEGER) FROM updates;
+DROP TABLE updates;
+ALTER TABLE updates_integer RENAME TO updates;
@@ -0,0 +1,19 @@
+-- chores removed from the config have their pending instances cancelled
+CREATE TABLE chores_cancellable (
+  id INTEGER NOT NULL PRIMARY KEY,
+  title TEXT NOT NULL,
+  expected_completion_time INTEGER NOT NULL,
+  status TEXT CHECK(status IN ('assigned', 'completed', 'missed', 'cancelled')) NOT NULL DEFAULT 'assigned',
+  created_at INTEGER NOT NULL DEFAULT (CAST(STRFTIME('%s', 'now') AS INTEGER)),
+  overdue_time INTEGER NOT NULL CHECK(overdue_time > expected_completion_time),
+  expiration_time INTEGER NOT NULL,
+  UNIQUE (title, expected_completion_time)
+);
+INSERT INTO chores_cancellable (id, title, expected_completion_time, status, created_at, overdue_time, expiration_time)
+  SELECT id, title, expected_completion_time, status, created_at, overdue_time, expiration_time
+  FROM chores;
+DROP TABLE chores;
+ALTER TABLE chores_cancellable RENAME TO chores;
+
+CREATE INDEX chores_expected_completion_time ON chores (expected_completion_time);
+CREATE INDEX chores_status_expiration_time ON chores (status, expiration_time);
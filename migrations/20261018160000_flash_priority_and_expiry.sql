@@ -0,0 +1,9 @@
+ALTER TABLE flashes ADD COLUMN priority TEXT CHECK(priority IN ('info', 'warning', 'urgent')) NOT NULL DEFAULT 'info';
+-- NULL to show the flash as soon as it is added
+ALTER TABLE flashes ADD COLUMN show_from INTEGER;
+-- NULL to show the flash until it is dismissed
+ALTER TABLE flashes ADD COLUMN expires_at INTEGER;
+ALTER TABLE flashes ADD COLUMN author TEXT;
+
+-- the scheduler's search for flashes that expired without being dismissed
+CREATE INDEX flashes_acknowledged_expires_at ON flashes (acknowledged, expires_at);
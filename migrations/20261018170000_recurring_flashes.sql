@@ -0,0 +1,6 @@
+-- flashes posted from the config's `flashes` section, keyed by name and fire time so each
+-- occurrence is only posted once
+ALTER TABLE flashes ADD COLUMN recurring_key TEXT;
+ALTER TABLE flashes ADD COLUMN recurring_time INTEGER;
+
+CREATE UNIQUE INDEX flashes_recurrence ON flashes (recurring_key, recurring_time);
@@ -0,0 +1,8 @@
+-- the household member who dismissed the flash
+ALTER TABLE flashes ADD COLUMN acknowledged_by TEXT;
+-- the household member the flash is addressed to, who is the only one that can dismiss it
+ALTER TABLE flashes ADD COLUMN recipient TEXT;
+ALTER TABLE flashes ADD COLUMN updated_at INTEGER;
+
+-- history, newest first
+CREATE INDEX flashes_created_at ON flashes (created_at);
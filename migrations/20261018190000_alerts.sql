@@ -0,0 +1,7 @@
+-- conditions that are currently raised, along with the flash posted for each, so the same condition
+-- only posts one flash until it clears
+CREATE TABLE alerts (
+    `key` TEXT PRIMARY KEY NOT NULL,
+    flash_id INTEGER NOT NULL,
+    raised_at INTEGER NOT NULL
+);
@@ -0,0 +1,10 @@
+-- the last good METAR observation at each station, so the dashboard isn't fetching from the NWS on
+-- every refresh and can keep showing something while a station is unreachable
+CREATE TABLE observations (
+    station TEXT PRIMARY KEY NOT NULL,
+    metar TEXT NOT NULL,
+    -- the observation decoded as a `StationMetar`, in JSON
+    decoded TEXT NOT NULL,
+    observed_at INTEGER,
+    fetched_at INTEGER NOT NULL
+);
@@ -0,0 +1,12 @@
+-- the latest fire time each entry in the config's `flashes` section was posted for, so deleting an
+-- upcoming flash doesn't get it posted again on the next pass
+CREATE TABLE recurring_flashes (
+    `key` TEXT PRIMARY KEY NOT NULL,
+    last_posted_time INTEGER NOT NULL
+);
+
+INSERT INTO recurring_flashes (`key`, last_posted_time)
+  SELECT recurring_key, MAX(recurring_time)
+  FROM flashes
+  WHERE recurring_key IS NOT NULL
+  GROUP BY recurring_key;
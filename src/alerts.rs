@@ -0,0 +1,511 @@
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::Arc;
+
+use anyhow::Result;
+use chores::api::{AddFlashParams, ApiEvent, ApiStatus, FlashPriority};
+use chrono::Utc;
+use serde::Deserialize;
+use sqlx::SqlitePool;
+use tokio::sync::broadcast::error::RecvError;
+use tokio::sync::Mutex;
+
+use crate::events::EventBus;
+use crate::{add_flash_impl, Config};
+
+const DUE_FORMAT: &str = "%a %b %-d %H:%M";
+
+/// Conditions that post a flash when they arise and retire it when they clear
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum AlertKind {
+    /// A chore was missed, until it's next completed
+    ChoreMissed,
+    /// A METAR station couldn't be fetched, until it next can be
+    WeatherFailed,
+    /// A pass of the scheduler failed, until one next succeeds
+    SchedulerFailed,
+}
+
+impl AlertKind {
+    fn name(self) -> &'static str {
+        match self {
+            AlertKind::ChoreMissed => "chore_missed",
+            AlertKind::WeatherFailed => "weather_failed",
+            AlertKind::SchedulerFailed => "scheduler_failed",
+        }
+    }
+
+    fn default_contents(self) -> &'static str {
+        match self {
+            AlertKind::ChoreMissed => "**{chore}** was missed (due {due})",
+            AlertKind::WeatherFailed => "Couldn't fetch the weather for {station}: {error}",
+            AlertKind::SchedulerFailed => "The scheduler failed: {error}",
+        }
+    }
+}
+
+/// The flash to post for a kind of alert
+#[derive(Deserialize, Debug)]
+pub struct AlertRule {
+    /// Markdown, where `{chore}`, `{due}`, `{station}` and `{error}` are replaced with the details
+    /// of what went wrong. Each kind has its own default
+    contents: Option<String>,
+    #[serde(default)]
+    priority: FlashPriority,
+    pub recipient: Option<String>,
+}
+
+/// Posts flashes for the conditions configured in `alerts`, one per condition until it clears
+pub struct Alerts {
+    pool: Arc<SqlitePool>,
+    config: Arc<Config>,
+    events: Arc<EventBus>,
+    /// Keeps a condition that's raised twice at once from posting two flashes
+    lock: Mutex<()>,
+}
+
+/// Identifies a condition, such as a particular chore being missed
+fn alert_key(kind: AlertKind, subject: Option<&str>) -> String {
+    match subject {
+        Some(subject) => format!("{}:{}", kind.name(), subject),
+        None => kind.name().to_string(),
+    }
+}
+
+impl Alerts {
+    pub fn new(pool: Arc<SqlitePool>, config: Arc<Config>, events: Arc<EventBus>) -> Arc<Self> {
+        Arc::new(Alerts {
+            pool,
+            config,
+            events,
+            lock: Mutex::new(()),
+        })
+    }
+
+    async fn raise(&self, kind: AlertKind, subject: Option<&str>, replacements: &[(&str, &str)]) {
+        let rule = match self.config.alerts.get(&kind) {
+            Some(rule) => rule,
+            None => return,
+        };
+
+        let key = alert_key(kind, subject);
+        if let Err(e) = self.try_raise(&key, kind, rule, replacements).await {
+            tracing::warn!("Failed to raise alert {}: {}", key, e);
+        }
+    }
+
+    async fn try_raise(
+        &self,
+        key: &str,
+        kind: AlertKind,
+        rule: &AlertRule,
+        replacements: &[(&str, &str)],
+    ) -> Result<()> {
+        let _guard = self.lock.lock().await;
+
+        // a flash deleted by hand leaves nothing on the dashboard, so it's posted again
+        let raised = sqlx::query(
+            r#"
+            SELECT 1
+            FROM `alerts`
+            JOIN `flashes` ON `flashes`.`id` = `alerts`.`flash_id`
+            WHERE `alerts`.`key` = ?1
+            "#,
+        )
+        .bind(key)
+        .fetch_optional(&*self.pool)
+        .await?
+        .is_some();
+        if raised {
+            return Ok(());
+        }
+
+        let mut contents = rule
+            .contents
+            .clone()
+            .unwrap_or_else(|| kind.default_contents().to_string());
+        for (name, value) in replacements {
+            contents = contents.replace(&format!("{{{}}}", name), value);
+        }
+
+        let params = AddFlashParams {
+            contents,
+            priority: rule.priority,
+            recipient: rule.recipient.clone(),
+            ..Default::default()
+        };
+        let flash_id =
+            add_flash_impl(params, self.pool.clone(), &self.config, &self.events).await?;
+
+        let now_timestamp = Utc::now().timestamp();
+        sqlx::query!(
+            r#"
+            INSERT INTO `alerts`
+            (
+                `key`,
+                `flash_id`,
+                `raised_at`
+            )
+            VALUES
+            (
+                ?1,
+                ?2,
+                ?3
+            )
+            ON CONFLICT (`key`) DO UPDATE SET
+                `flash_id` = excluded.`flash_id`,
+                `raised_at` = excluded.`raised_at`
+            "#,
+            key,
+            flash_id,
+            now_timestamp,
+        )
+        .execute(&*self.pool)
+        .await?;
+
+        tracing::info!("Raised alert {}", key);
+
+        Ok(())
+    }
+
+    async fn clear(&self, kind: AlertKind, subject: Option<&str>) {
+        let key = alert_key(kind, subject);
+        if let Err(e) = self.try_clear(&key).await {
+            tracing::warn!("Failed to clear alert {}: {}", key, e);
+        }
+    }
+
+    /// Retires the flash posted for a condition, as if it had been dismissed when the condition
+    /// cleared
+    async fn try_clear(&self, key: &str) -> Result<()> {
+        let _guard = self.lock.lock().await;
+
+        let flash_id: Option<i64> =
+            sqlx::query_scalar("SELECT `flash_id` FROM `alerts` WHERE `key` = ?1")
+                .bind(key)
+                .fetch_optional(&*self.pool)
+                .await?;
+        let flash_id = match flash_id {
+            Some(flash_id) => flash_id,
+            None => return Ok(()),
+        };
+
+        let now_timestamp = Utc::now().timestamp();
+        let mut txn = self.pool.begin().await?;
+
+        sqlx::query!("DELETE FROM `alerts` WHERE `key` = ?1", key)
+            .execute(&mut txn)
+            .await?;
+
+        // the flash may have been dismissed or deleted by hand in the meantime
+        let dismissed = sqlx::query!(
+            r#"
+            UPDATE `flashes`
+            SET
+                `acknowledged` = 1,
+                `acknowledged_at` = ?2
+            WHERE
+                `id` = ?1
+                AND `acknowledged` = 0
+            "#,
+            flash_id,
+            now_timestamp,
+        )
+        .execute(&mut txn)
+        .await?
+        .rows_affected()
+            > 0;
+
+        txn.commit().await?;
+
+        tracing::info!("Cleared alert {}", key);
+        if dismissed {
+            self.events
+                .publish(ApiEvent::FlashDismissed { id: flash_id });
+        }
+
+        Ok(())
+    }
+
+    async fn chore_missed(&self, title: &str, expected_completion_time: i64) {
+        let chore = match self.config.chores.get(title) {
+            Some(chore) => chore.display_name(title),
+            None => title,
+        };
+        let due = match self.config.localize_timestamp(expected_completion_time) {
+            Ok(due) => due.format(DUE_FORMAT).to_string(),
+            Err(_) => expected_completion_time.to_string(),
+        };
+
+        self.raise(
+            AlertKind::ChoreMissed,
+            Some(title),
+            &[("chore", chore), ("due", &due)],
+        )
+        .await;
+    }
+
+    /// Raises alerts for the stations that couldn't be fetched, and clears them for the rest
+    pub async fn weather_fetched(&self, failures: &HashMap<String, String>) {
+        for station in self.config.metar_stations.iter() {
+            let station = station.id();
+            match failures.get(station) {
+                Some(error) => {
+                    self.raise(
+                        AlertKind::WeatherFailed,
+                        Some(station),
+                        &[("station", station), ("error", error)],
+                    )
+                    .await
+                }
+                None => self.clear(AlertKind::WeatherFailed, Some(station)).await,
+            }
+        }
+    }
+
+    pub async fn scheduler_failed(&self, error: &anyhow::Error) {
+        let error = error.to_string();
+        self.raise(AlertKind::SchedulerFailed, None, &[("error", &error)])
+            .await;
+    }
+
+    pub async fn scheduler_succeeded(&self) {
+        self.clear(AlertKind::SchedulerFailed, None).await;
+    }
+
+    /// Clears the chore alerts whose chore has since been completed or cancelled, for when the events
+    /// that would have cleared them were skipped. A chore's alert stays raised while the latest of its
+    /// instances that isn't still assigned is missed
+    async fn reconcile_chores(&self) -> Result<()> {
+        let prefix = alert_key(AlertKind::ChoreMissed, Some(""));
+        let keys: Vec<String> = sqlx::query_scalar("SELECT `key` FROM `alerts`")
+            .fetch_all(&*self.pool)
+            .await?;
+
+        for key in keys {
+            let title = match key.strip_prefix(&prefix) {
+                Some(title) => title,
+                None => continue,
+            };
+
+            let status: Option<String> = sqlx::query_scalar(
+                r#"
+                SELECT `status`
+                FROM `chores`
+                WHERE
+                    `title` = ?1
+                    AND `status` != 'assigned'
+                ORDER BY `expected_completion_time` DESC
+                LIMIT 1
+                "#,
+            )
+            .bind(title)
+            .fetch_optional(&*self.pool)
+            .await?;
+
+            if status.as_deref() != Some("missed") {
+                self.clear(AlertKind::ChoreMissed, Some(title)).await;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Raises and clears chore alerts as chores are missed, completed and cancelled
+    pub fn watch_chores(self: Arc<Self>) -> impl Future<Output = Result<()>> {
+        // subscribed right away, so the events of the scheduler's first pass aren't missed
+        let mut receiver = self.events.listen();
+
+        async move {
+            loop {
+                let event = match receiver.recv().await {
+                    Ok((_, event)) => event,
+                    Err(RecvError::Lagged(skipped)) => {
+                        tracing::warn!("Alerts fell behind and skipped {} event(s)", skipped);
+                        if let Err(e) = self.reconcile_chores().await {
+                            tracing::warn!("Failed to reconcile chore alerts: {}", e);
+                        }
+                        continue;
+                    }
+                    Err(RecvError::Closed) => return Ok(()),
+                };
+
+                match event {
+                    ApiEvent::ChoreStatusChanged {
+                        title,
+                        expected_completion_time,
+                        status: ApiStatus::Missed,
+                        ..
+                    } => self.chore_missed(&title, expected_completion_time).await,
+                    ApiEvent::ChoreCompleted { title, .. }
+                    | ApiEvent::ChoreStatusChanged {
+                        title,
+                        status: ApiStatus::Cancelled,
+                        ..
+                    } => self.clear(AlertKind::ChoreMissed, Some(&title)).await,
+                    _ => {}
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::delete_flash_impl;
+    use crate::tests::memory_pool;
+
+    async fn alerts() -> Arc<Alerts> {
+        let config = serde_json::from_value(serde_json::json!({
+            "chores": {},
+            "metar_stations": [],
+            "overdue_time": "1h",
+            "alerts": { "chore_missed": {} },
+        }))
+        .unwrap();
+
+        Alerts::new(
+            Arc::new(memory_pool().await),
+            Arc::new(config),
+            EventBus::new(),
+        )
+    }
+
+    /// `(acknowledged, contents)` of every flash, oldest first
+    async fn flashes(alerts: &Alerts) -> Vec<(bool, String)> {
+        sqlx::query_as("SELECT `acknowledged`, `contents` FROM `flashes` ORDER BY `id`")
+            .fetch_all(&*alerts.pool)
+            .await
+            .unwrap()
+    }
+
+    async fn raised(alerts: &Alerts) -> Vec<String> {
+        sqlx::query_scalar("SELECT `key` FROM `alerts` ORDER BY `key`")
+            .fetch_all(&*alerts.pool)
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn raises_once_until_cleared() {
+        let alerts = alerts().await;
+
+        alerts.chore_missed("trash", 0).await;
+        alerts.chore_missed("trash", 86400).await;
+        assert_eq!(
+            flashes(&alerts).await,
+            [(
+                false,
+                "**trash** was missed (due Thu Jan 1 00:00)".to_string()
+            )]
+        );
+        assert_eq!(raised(&alerts).await, ["chore_missed:trash"]);
+
+        alerts.clear(AlertKind::ChoreMissed, Some("trash")).await;
+        assert_eq!(
+            flashes(&alerts).await,
+            [(
+                true,
+                "**trash** was missed (due Thu Jan 1 00:00)".to_string()
+            )]
+        );
+        assert!(raised(&alerts).await.is_empty());
+
+        alerts.chore_missed("trash", 86400).await;
+        assert_eq!(flashes(&alerts).await.len(), 2);
+        assert!(!flashes(&alerts).await[1].0);
+    }
+
+    #[tokio::test]
+    async fn dismissed_alert_stays_dismissed_until_cleared() {
+        let alerts = alerts().await;
+
+        alerts.chore_missed("trash", 0).await;
+        sqlx::query("UPDATE `flashes` SET `acknowledged` = 1")
+            .execute(&*alerts.pool)
+            .await
+            .unwrap();
+
+        alerts.chore_missed("trash", 86400).await;
+        assert_eq!(flashes(&alerts).await.len(), 1);
+
+        alerts.clear(AlertKind::ChoreMissed, Some("trash")).await;
+        alerts.chore_missed("trash", 172800).await;
+        let flashes = flashes(&alerts).await;
+        assert_eq!(flashes.len(), 2);
+        assert!(!flashes[1].0);
+    }
+
+    #[tokio::test]
+    async fn deleted_alert_is_raised_again() {
+        let alerts = alerts().await;
+
+        alerts.chore_missed("trash", 0).await;
+        let id: i64 = sqlx::query_scalar("SELECT `id` FROM `flashes`")
+            .fetch_one(&*alerts.pool)
+            .await
+            .unwrap();
+        delete_flash_impl(id, alerts.pool.clone(), &alerts.events)
+            .await
+            .unwrap();
+
+        alerts.chore_missed("trash", 86400).await;
+        let posted = flashes(&alerts).await;
+        assert_eq!(posted.len(), 1);
+        assert!(!posted[0].0);
+        assert_eq!(raised(&alerts).await, ["chore_missed:trash"]);
+
+        // and cleared as usual
+        alerts.clear(AlertKind::ChoreMissed, Some("trash")).await;
+        assert!(flashes(&alerts).await[0].0);
+    }
+
+    #[tokio::test]
+    async fn reconciles_chore_alerts() {
+        let alerts = alerts().await;
+        for (title, time, status) in [
+            ("trash", 0, "missed"),
+            ("trash", 86400, "completed"),
+            ("dishes", 0, "completed"),
+            ("dishes", 86400, "missed"),
+            ("dishes", 172800, "assigned"),
+            ("laundry", 0, "missed"),
+            ("laundry", 86400, "cancelled"),
+        ] {
+            sqlx::query(
+                r#"
+                INSERT INTO `chores`
+                (`title`, `expected_completion_time`, `status`, `overdue_time`, `expiration_time`)
+                VALUES (?1, ?2, ?3, ?2 + 3600, ?2 + 86400)
+                "#,
+            )
+            .bind(title)
+            .bind(time)
+            .bind(status)
+            .execute(&*alerts.pool)
+            .await
+            .unwrap();
+        }
+
+        for title in ["trash", "dishes", "laundry", "removed"] {
+            alerts.chore_missed(title, 0).await;
+        }
+        alerts.reconcile_chores().await.unwrap();
+
+        assert_eq!(raised(&alerts).await, ["chore_missed:dishes"]);
+        let unacknowledged: Vec<_> = flashes(&alerts)
+            .await
+            .into_iter()
+            .filter(|(acknowledged, _)| !acknowledged)
+            .collect();
+        assert_eq!(
+            unacknowledged,
+            [(
+                false,
+                "**dishes** was missed (due Thu Jan 1 00:00)".to_string()
+            )]
+        );
+    }
+}
@@ -0,0 +1,342 @@
+use std::collections::HashMap;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+use crate::weather::StationMetar;
+
+#[derive(Serialize, Deserialize, Debug, Clone, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ApiStatus {
+    Upcoming,
+    Assigned,
+    Overdue,
+    Completed,
+    Missed,
+    /// The chore was removed from the config before this instance was due
+    Cancelled,
+}
+
+impl ApiStatus {
+    /// Position on the dashboard, with the most pressing chores first
+    pub fn priority(&self) -> u8 {
+        match self {
+            ApiStatus::Overdue => 0,
+            ApiStatus::Assigned => 1,
+            ApiStatus::Upcoming => 2,
+            ApiStatus::Missed => 3,
+            ApiStatus::Completed => 4,
+            ApiStatus::Cancelled => 5,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, ToSchema)]
+pub struct ApiChore {
+    pub id: i64,
+    /// Stable key of the chore in the config, which its history is stored under
+    pub title: String,
+    /// Label to show for the chore, which may change without affecting its history
+    pub display_name: String,
+    /// Markdown
+    pub description: String,
+    /// `description` rendered to sanitized HTML
+    pub description_html: String,
+    pub expected_completion_time: i64,
+    /// `expected_completion_time` as RFC 3339 in the configured timezone
+    pub expected_completion_at: String,
+    /// Human-readable rendering of the chore's frequency
+    pub schedule: String,
+    pub status: ApiStatus,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, ToSchema)]
+pub struct ListChoresResponse {
+    pub success: bool,
+    pub error: Option<String>,
+    pub chores: Vec<ApiChore>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ListChoresParams {
+    pub lookback_days: Option<u32>,
+}
+
+/// A chore that was removed from the config, with every instance it had
+#[derive(Serialize, Deserialize, Debug, Clone, ToSchema)]
+pub struct ArchivedChore {
+    pub title: String,
+    pub instances: Vec<ArchivedChoreInstance>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, ToSchema)]
+pub struct ArchivedChoreInstance {
+    pub id: i64,
+    pub expected_completion_time: i64,
+    pub status: ApiStatus,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, ToSchema)]
+pub struct ListArchivedChoresResponse {
+    pub success: bool,
+    pub error: Option<String>,
+    pub chores: Vec<ArchivedChore>,
+}
+
+/// Identifies the chore instance by `id`, or by `title` and `expected_completion_time` together
+#[derive(Serialize, Deserialize, Debug, Default, ToSchema)]
+pub struct CompleteChoreParams {
+    pub id: Option<i64>,
+    pub title: Option<String>,
+    pub expected_completion_time: Option<i64>,
+}
+
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+pub struct CompleteChoreResponse {
+    pub success: bool,
+    pub error: Option<String>,
+}
+
+/// How prominently a flash is shown, with more urgent flashes first
+#[derive(
+    Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, ToSchema,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum FlashPriority {
+    #[default]
+    Info,
+    Warning,
+    Urgent,
+}
+
+impl FlashPriority {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FlashPriority::Info => "info",
+            FlashPriority::Warning => "warning",
+            FlashPriority::Urgent => "urgent",
+        }
+    }
+}
+
+impl FromStr for FlashPriority {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "info" => Ok(FlashPriority::Info),
+            "warning" => Ok(FlashPriority::Warning),
+            "urgent" => Ok(FlashPriority::Urgent),
+            _ => Err(format!("Unknown flash priority \"{}\"", value)),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct Flash {
+    pub id: i64,
+    /// Markdown
+    pub contents: String,
+    /// `contents` rendered to sanitized HTML
+    pub contents_html: String,
+    pub created_at: i64,
+    pub priority: FlashPriority,
+    pub show_from: Option<i64>,
+    pub expires_at: Option<i64>,
+    pub author: Option<String>,
+    /// Household member the flash is addressed to, who is the only one that can dismiss it
+    pub recipient: Option<String>,
+    /// When the flash was last edited, if ever
+    pub updated_at: Option<i64>,
+}
+
+/// A flash in `/api/flashes/history`, which includes those already dismissed
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct FlashHistoryEntry {
+    #[serde(flatten)]
+    pub flash: Flash,
+    pub acknowledged: bool,
+    pub acknowledged_at: Option<i64>,
+    pub acknowledged_by: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default, ToSchema, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct FlashHistoryParams {
+    /// Flashes per page, 50 by default and at most 200
+    pub limit: Option<u32>,
+    /// Number of flashes to skip, newest first
+    pub offset: Option<u32>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct FlashHistoryPage {
+    pub flashes: Vec<FlashHistoryEntry>,
+    /// Number of flashes across every page
+    pub total: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct FlashHistoryResponse {
+    pub success: bool,
+    pub error: Option<String>,
+    pub flashes: Vec<FlashHistoryEntry>,
+    pub total: i64,
+}
+
+// TODO: make into flattened enum
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct GetFlashResponse {
+    pub success: bool,
+    pub error: Option<String>,
+    pub flashes: Vec<Flash>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default, ToSchema)]
+pub struct AddFlashParams {
+    pub contents: String,
+    #[serde(default)]
+    pub priority: FlashPriority,
+    /// Epoch seconds to start showing the flash at, instead of right away
+    pub show_from: Option<i64>,
+    /// Epoch seconds to stop showing the flash at, instead of once it's dismissed
+    pub expires_at: Option<i64>,
+    pub author: Option<String>,
+    /// Household member to address the flash to
+    pub recipient: Option<String>,
+}
+
+/// Replaces everything about a flash other than its author
+#[derive(Debug, Serialize, Deserialize, Default, ToSchema)]
+pub struct UpdateFlashParams {
+    pub contents: String,
+    #[serde(default)]
+    pub priority: FlashPriority,
+    pub show_from: Option<i64>,
+    pub expires_at: Option<i64>,
+    pub recipient: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+pub struct UpdateFlashResponse {
+    pub success: bool,
+    pub error: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+pub struct DeleteFlashResponse {
+    pub success: bool,
+    pub error: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+pub struct AddFlashResponse {
+    pub success: bool,
+    pub id: Option<i64>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct DismissFlashParams {
+    pub id: i64,
+    /// Household member dismissing the flash, which must be its recipient if it has one
+    pub member: Option<String>,
+}
+
+// TODO: make this a common type
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+pub struct DismissFlashResponse {
+    pub success: bool,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct GetMetarsResponse {
+    pub stations: HashMap<String, StationMetar>,
+}
+
+/// Changes streamed from `/api/events`, named by `type`
+#[derive(Serialize, Deserialize, Debug, Clone, ToSchema)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ApiEvent {
+    ChoreCreated {
+        id: i64,
+        title: String,
+        expected_completion_time: i64,
+    },
+    ChoreCompleted {
+        id: i64,
+        title: String,
+        expected_completion_time: i64,
+    },
+    ChoreStatusChanged {
+        id: i64,
+        title: String,
+        expected_completion_time: i64,
+        status: ApiStatus,
+    },
+    FlashAdded {
+        flash: Flash,
+    },
+    FlashDismissed {
+        id: i64,
+    },
+    FlashUpdated {
+        flash: Flash,
+    },
+    FlashDeleted {
+        id: i64,
+    },
+    WeatherUpdated {
+        stations: HashMap<String, StationMetar>,
+    },
+}
+
+impl ApiEvent {
+    pub fn name(&self) -> &'static str {
+        match self {
+            ApiEvent::ChoreCreated { .. } => "chore_created",
+            ApiEvent::ChoreCompleted { .. } => "chore_completed",
+            ApiEvent::ChoreStatusChanged { .. } => "chore_status_changed",
+            ApiEvent::FlashAdded { .. } => "flash_added",
+            ApiEvent::FlashDismissed { .. } => "flash_dismissed",
+            ApiEvent::FlashUpdated { .. } => "flash_updated",
+            ApiEvent::FlashDeleted { .. } => "flash_deleted",
+            ApiEvent::WeatherUpdated { .. } => "weather_updated",
+        }
+    }
+}
+
+/// Body of a successful `/api/v2` response
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+pub struct ApiData<T> {
+    pub data: T,
+}
+
+/// Body of a failed `/api/v2` response, sent with a matching HTTP status
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+pub struct ApiErrorResponse {
+    pub error: ApiErrorBody,
+}
+
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+pub struct ApiErrorBody {
+    /// One of `bad_request`, `forbidden`, `not_found`, `conflict` or `internal`
+    pub code: String,
+    pub message: String,
+}
+
+/// Identifies a chore instance in `/api/v2` responses
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+pub struct ChoreInstance {
+    pub id: i64,
+    pub title: String,
+    pub expected_completion_time: i64,
+}
+
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+pub struct FlashId {
+    pub id: i64,
+}
@@ -0,0 +1,132 @@
+use std::borrow::Cow;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::path::{Component, Path, PathBuf};
+use std::sync::Arc;
+
+use axum::body::{self, Bytes, Full};
+use axum::extract::Path as UrlPath;
+use axum::http::{header, HeaderMap, HeaderValue, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::Extension;
+
+include!(concat!(env!("OUT_DIR"), "/assets.rs"));
+
+const INDEX_KEY: &str = "assets/html/index.html";
+
+#[derive(Clone)]
+pub struct Asset {
+    contents: Cow<'static, [u8]>,
+    etag: String,
+}
+
+impl Asset {
+    fn new(contents: Cow<'static, [u8]>) -> Self {
+        let mut hasher = DefaultHasher::new();
+        contents.hash(&mut hasher);
+
+        Asset {
+            contents,
+            // weak, as the compression layer may re-encode the body
+            etag: format!("W/\"{:016x}\"", hasher.finish()),
+        }
+    }
+}
+
+/// Frontend files, either embedded at build time or read from a checkout on each request
+pub enum Assets {
+    Embedded(HashMap<&'static str, Asset>),
+    Disk(PathBuf),
+}
+
+impl Assets {
+    pub fn load(dir: Option<PathBuf>) -> Arc<Self> {
+        let assets = match dir {
+            Some(dir) => {
+                tracing::info!("serving frontend assets from {}", dir.display());
+                Assets::Disk(dir)
+            }
+            None => Assets::Embedded(
+                EMBEDDED_ASSETS
+                    .iter()
+                    .map(|(key, contents)| (*key, Asset::new(Cow::Borrowed(*contents))))
+                    .collect(),
+            ),
+        };
+
+        Arc::new(assets)
+    }
+
+    fn get(&self, key: &str) -> Option<Asset> {
+        match self {
+            Assets::Embedded(assets) => assets.get(key).cloned(),
+            Assets::Disk(dir) => {
+                let path = Path::new(key);
+                if !path.components().all(|c| matches!(c, Component::Normal(_))) {
+                    return None;
+                }
+
+                let contents = std::fs::read(dir.join(path)).ok()?;
+                Some(Asset::new(Cow::Owned(contents)))
+            }
+        }
+    }
+}
+
+fn asset_response(assets: &Assets, key: &str, headers: &HeaderMap) -> Response {
+    let asset = match assets.get(key) {
+        Some(asset) => asset,
+        None => return (StatusCode::NOT_FOUND, "Not found").into_response(),
+    };
+
+    let etag = HeaderValue::from_str(&asset.etag).unwrap();
+    let not_modified = headers
+        .get(header::IF_NONE_MATCH)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| {
+            value.split(',').any(|tag| {
+                let tag = tag.trim();
+                tag == "*" || tag.trim_start_matches("W/") == asset.etag.trim_start_matches("W/")
+            })
+        });
+
+    let response = Response::builder()
+        .header(header::ETAG, etag)
+        // cached copies must be revalidated, which is cheap thanks to the ETag
+        .header(header::CACHE_CONTROL, "no-cache");
+
+    if not_modified {
+        return response
+            .status(StatusCode::NOT_MODIFIED)
+            .body(body::boxed(body::Empty::new()))
+            .unwrap();
+    }
+
+    let mime_type = mime_guess::from_path(key).first_or_octet_stream();
+
+    response
+        .status(StatusCode::OK)
+        .header(
+            header::CONTENT_TYPE,
+            HeaderValue::from_str(mime_type.as_ref()).unwrap(),
+        )
+        .body(body::boxed(Full::from(match asset.contents {
+            Cow::Borrowed(contents) => Bytes::from_static(contents),
+            Cow::Owned(contents) => Bytes::from(contents),
+        })))
+        .unwrap()
+}
+
+pub async fn index(headers: HeaderMap, Extension(assets): Extension<Arc<Assets>>) -> Response {
+    asset_response(&assets, INDEX_KEY, &headers)
+}
+
+pub async fn dist(
+    UrlPath(path): UrlPath<String>,
+    headers: HeaderMap,
+    Extension(assets): Extension<Arc<Assets>>,
+) -> Response {
+    let key = format!("dist/{}", path.trim_start_matches('/'));
+    asset_response(&assets, &key, &headers)
+}
@@ -0,0 +1,129 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// What the scheduler does with occurrences that fully elapsed while the server was down
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, Default, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum CatchUpPolicy {
+    /// Insert every missed occurrence, each of which is then marked as missed
+    #[default]
+    Backfill,
+    /// Insert only the most recent missed occurrence
+    Collapse,
+    /// Drop missed occurrences without recording them
+    Skip,
+}
+
+#[derive(Serialize, Debug, Clone, ToSchema)]
+pub struct CaughtUpChore {
+    pub title: String,
+    pub policy: CatchUpPolicy,
+    pub missed_occurrences: usize,
+    pub backfilled: usize,
+}
+
+#[derive(Serialize, Debug, Clone, ToSchema)]
+pub struct CatchUpReport {
+    pub last_update: i64,
+    pub caught_up_at: i64,
+    pub chores: Vec<CaughtUpChore>,
+}
+
+impl CatchUpReport {
+    /// A flash summarizing the chores that were caught up with, listing as many as fit in
+    /// `max_length` characters and counting the rest
+    pub fn flash_contents(&self, max_length: usize) -> Option<String> {
+        let mut summaries = Vec::new();
+        for chore in self.chores.iter() {
+            let summary = match chore.policy {
+                CatchUpPolicy::Backfill => format!(
+                    "{} ({} backfilled as missed)",
+                    chore.title, chore.backfilled
+                ),
+                CatchUpPolicy::Collapse => format!(
+                    "{} ({} collapsed into one)",
+                    chore.title, chore.missed_occurrences
+                ),
+                CatchUpPolicy::Skip => {
+                    format!("{} ({} skipped)", chore.title, chore.missed_occurrences)
+                }
+            };
+            summaries.push(summary);
+        }
+
+        if summaries.is_empty() {
+            return None;
+        }
+
+        let mut contents = String::from("Caught up on chores missed during downtime: ");
+        let mut length = contents.chars().count();
+        for (index, summary) in summaries.iter().enumerate() {
+            let separator = if index == 0 { "" } else { ", " };
+            let remaining = summaries.len() - index - 1;
+            let more = match remaining {
+                0 => String::new(),
+                _ => format!(", and {} more", remaining),
+            };
+
+            let summary_length = separator.chars().count() + summary.chars().count();
+            if length + summary_length + more.chars().count() > max_length {
+                let more = match index {
+                    0 => format!("{} chores", summaries.len()),
+                    _ => format!(", and {} more", summaries.len() - index),
+                };
+                contents.push_str(&more);
+                break;
+            }
+
+            contents.push_str(separator);
+            contents.push_str(summary);
+            length += summary_length;
+        }
+
+        Some(contents)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn report(count: usize) -> CatchUpReport {
+        CatchUpReport {
+            last_update: 0,
+            caught_up_at: 86400,
+            chores: (0..count)
+                .map(|index| CaughtUpChore {
+                    title: format!("chore {}", index),
+                    policy: CatchUpPolicy::Skip,
+                    missed_occurrences: 3,
+                    backfilled: 0,
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn lists_every_chore_that_fits() {
+        assert_eq!(report(0).flash_contents(2000), None);
+        assert_eq!(
+            report(2).flash_contents(2000).unwrap(),
+            "Caught up on chores missed during downtime: chore 0 (3 skipped), chore 1 (3 skipped)"
+        );
+    }
+
+    #[test]
+    fn counts_chores_that_dont_fit() {
+        let contents = report(500).flash_contents(2000).unwrap();
+        assert!(contents.chars().count() <= 2000);
+        assert!(contents
+            .starts_with("Caught up on chores missed during downtime: chore 0 (3 skipped), "));
+        let shown = contents.matches("skipped").count();
+        assert!(contents.ends_with(&format!(", and {} more", 500 - shown)));
+
+        assert_eq!(
+            report(500).flash_contents(60).unwrap(),
+            "Caught up on chores missed during downtime: 500 chores"
+        );
+    }
+}
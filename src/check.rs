@@ -0,0 +1,283 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::fs::read_to_string;
+
+use anyhow::Result;
+use chores::weather_source::{MetarStation, WeatherSourceConfig};
+use chrono::Duration;
+use serde::de::{IgnoredAny, MapAccess, Visitor};
+use serde::{Deserialize, Deserializer};
+
+use crate::schedule::ChoreSchedule;
+use crate::Config;
+
+/// How far ahead to look when comparing `overdue_time` against the gaps between occurrences
+const CHECK_WINDOW_DAYS: i64 = 366;
+
+/// Keys of a JSON object in the order they appear, including repeats that a map would drop
+struct ObjectKeys(Vec<String>);
+
+impl<'de> Deserialize<'de> for ObjectKeys {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct KeysVisitor;
+
+        impl<'de> Visitor<'de> for KeysVisitor {
+            type Value = ObjectKeys;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("an object")
+            }
+
+            fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+            where
+                A: MapAccess<'de>,
+            {
+                let mut keys = Vec::new();
+                while let Some((key, IgnoredAny)) = map.next_entry::<String, IgnoredAny>()? {
+                    keys.push(key);
+                }
+
+                Ok(ObjectKeys(keys))
+            }
+        }
+
+        deserializer.deserialize_map(KeysVisitor)
+    }
+}
+
+#[derive(Deserialize)]
+struct RawConfig {
+    chores: Option<ObjectKeys>,
+}
+
+fn check_duplicate_titles(contents: &str, problems: &mut Vec<String>) {
+    let keys = match serde_json::from_str::<RawConfig>(contents) {
+        Ok(RawConfig { chores: Some(keys) }) => keys.0,
+        _ => return,
+    };
+
+    let mut seen: HashMap<String, &str> = HashMap::new();
+    for key in keys.iter() {
+        let normalized = key.trim().to_lowercase();
+        match seen.get(&normalized) {
+            Some(existing) if *existing == key => {
+                problems.push(format!("Chore \"{}\" is defined more than once", key))
+            }
+            Some(existing) => problems.push(format!(
+                "Chores \"{}\" and \"{}\" only differ by case or whitespace",
+                existing, key
+            )),
+            None => {
+                seen.insert(normalized, key);
+            }
+        }
+    }
+}
+
+/// Parses each chore's and recurring flash's frequency on its own so that every bad one is
+/// reported, returning the parse errors so they aren't repeated when the whole config fails to
+/// deserialize
+fn check_frequencies(config: &serde_json::Value, problems: &mut Vec<String>) -> Vec<String> {
+    let mut errors = Vec::new();
+
+    for (section, kind) in [("chores", "Chore"), ("flashes", "Flash")] {
+        let entries = match config.get(section).and_then(|entries| entries.as_object()) {
+            Some(entries) => entries,
+            None => continue,
+        };
+
+        for (name, entry) in entries.iter() {
+            match entry.get("frequency") {
+                Some(serde_json::Value::String(frequency)) => {
+                    if let Err(e) = frequency.parse::<ChoreSchedule>() {
+                        problems.push(format!("{} \"{}\": {}", kind, name, e));
+                        errors.push(e.to_string());
+                    }
+                }
+                Some(_) => {
+                    problems.push(format!("{} \"{}\": frequency must be a string", kind, name))
+                }
+                None => problems.push(format!("{} \"{}\" has no frequency", kind, name)),
+            }
+        }
+    }
+
+    errors
+}
+
+fn check_overdue_times(config: &Config, problems: &mut Vec<String>) -> Result<()> {
+    let overdue_time = Duration::from_std(config.overdue_time)?;
+
+    if overdue_time <= Duration::zero() {
+        problems.push(
+            "overdue_time must be positive, as chores must become overdue after they are due"
+                .to_string(),
+        );
+    }
+
+    let now = config.now();
+    let until = now + Duration::days(CHECK_WINDOW_DAYS);
+
+    for (title, chore) in config.chores.iter() {
+        let occurrences = chore.frequency.occurrences(&now, &until);
+        let shortest_gap = occurrences.windows(2).map(|pair| pair[1] - pair[0]).min();
+
+        match shortest_gap {
+            Some(gap) if overdue_time >= gap => problems.push(format!(
+                "Chore \"{}\" recurs after as little as {}, which is before it would become \
+                 overdue after {}",
+                title,
+                humantime::format_duration(gap.to_std()?),
+                humantime::format_duration(config.overdue_time),
+            )),
+            Some(_) => {}
+            None => problems.push(format!(
+                "Chore \"{}\" ({}) has fewer than two occurrences in the next {} days, so \
+                 it would never be scheduled",
+                title,
+                chore.frequency.describe(),
+                CHECK_WINDOW_DAYS,
+            )),
+        }
+    }
+
+    Ok(())
+}
+
+/// Each alias must belong to a single chore and not be the key of another, display names must
+/// tell chores apart, and members must tell people apart and include everyone alerts go to
+fn check_names(config: &Config, problems: &mut Vec<String>) {
+    let mut titles: Vec<&String> = config.chores.keys().collect();
+    titles.sort();
+
+    let mut alias_owners: HashMap<&str, &str> = HashMap::new();
+    let mut display_names: HashMap<String, &str> = HashMap::new();
+    for title in titles {
+        let chore = &config.chores[title];
+
+        for alias in chore.aliases.iter() {
+            if config.chores.contains_key(alias) {
+                problems.push(format!(
+                    "Chore \"{}\" has alias \"{}\", which is the key of another chore",
+                    title, alias
+                ));
+            } else if let Some(owner) = alias_owners.insert(alias, title) {
+                problems.push(format!(
+                    "Alias \"{}\" belongs to both \"{}\" and \"{}\"",
+                    alias, owner, title
+                ));
+            }
+        }
+
+        let display_name = chore.display_name(title);
+        if let Some(other) = display_names.insert(display_name.trim().to_lowercase(), title) {
+            problems.push(format!(
+                "Chores \"{}\" and \"{}\" are both displayed as \"{}\"",
+                other, title, display_name
+            ));
+        }
+    }
+
+    let mut members = HashSet::new();
+    for member in config.members.iter() {
+        if member.trim().is_empty() {
+            problems.push("Members must not be blank".to_string());
+        } else if !members.insert(member.trim().to_lowercase()) {
+            problems.push(format!("Member \"{}\" is listed more than once", member));
+        }
+    }
+
+    if members.is_empty() {
+        return;
+    }
+    for rule in config.alerts.values() {
+        if let Some(recipient) = &rule.recipient {
+            if !members.contains(&recipient.trim().to_lowercase()) {
+                problems.push(format!(
+                    "Alerts go to \"{}\", who isn't one of the members",
+                    recipient
+                ));
+            }
+        }
+    }
+}
+
+/// Stations must be listed once, and local sources must exist
+fn check_weather_sources(config: &Config, problems: &mut Vec<String>) {
+    let mut stations = HashSet::new();
+    for station in config.metar_stations.iter() {
+        if !stations.insert(station.id().to_uppercase()) {
+            problems.push(format!(
+                "Station \"{}\" is listed more than once",
+                station.id()
+            ));
+        }
+    }
+
+    let sources = config
+        .metar_stations
+        .iter()
+        .filter_map(|station| match station {
+            MetarStation::WithSource { source, .. } => Some(source),
+            MetarStation::Id(_) => None,
+        })
+        .chain([&config.weather_source]);
+    for source in sources {
+        if let WeatherSourceConfig::File(source) = source {
+            if !source.path.exists() {
+                problems.push(format!(
+                    "Weather source {} doesn't exist",
+                    source.path.display()
+                ));
+            }
+        }
+    }
+}
+
+/// Validates the config at `path`, returning every problem found rather than stopping at the first
+pub fn check_config(path: &str) -> Result<Vec<String>> {
+    let contents = read_to_string(path)?;
+    let mut problems = Vec::new();
+
+    let value = match serde_json::from_str::<serde_json::Value>(&contents) {
+        Ok(value) => value,
+        Err(e) => {
+            problems.push(format!("Invalid JSON: {}", e));
+            return Ok(problems);
+        }
+    };
+
+    check_duplicate_titles(&contents, &mut problems);
+    let frequency_errors = check_frequencies(&value, &mut problems);
+
+    let mut unknown_fields = Vec::new();
+    let deserializer = &mut serde_json::Deserializer::from_str(&contents);
+    let result: Result<Config, _> =
+        serde_ignored::deserialize(deserializer, |path| unknown_fields.push(path.to_string()));
+
+    problems.extend(
+        unknown_fields
+            .into_iter()
+            .map(|field| format!("Unknown field \"{}\"", field)),
+    );
+
+    let config = match result {
+        Ok(config) => config,
+        Err(e) => {
+            let message = e.to_string();
+            if !frequency_errors.iter().any(|error| message.contains(error)) {
+                problems.push(format!("Invalid config: {}", e));
+            }
+            return Ok(problems);
+        }
+    };
+
+    check_names(&config, &mut problems);
+    check_weather_sources(&config, &mut problems);
+    check_overdue_times(&config, &mut problems)?;
+
+    Ok(problems)
+}
@@ -0,0 +1,592 @@
+use std::fs::read_to_string;
+use std::path::PathBuf;
+
+use anyhow::{anyhow, Result};
+use chores::api::{
+    AddFlashParams, AddFlashResponse, ApiChore, ApiStatus, CompleteChoreParams,
+    CompleteChoreResponse, DeleteFlashResponse, DismissFlashParams, DismissFlashResponse,
+    FlashHistoryResponse, FlashPriority, GetFlashResponse, GetMetarsResponse, ListChoresResponse,
+};
+use chrono::{DateTime, TimeZone, Utc};
+use clap::{Args, Subcommand};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use crate::table;
+use crate::OutputFormat;
+
+const TABLE_TIME_FORMAT: &str = "%a %Y-%m-%d %H:%M";
+
+fn default_server_url() -> String {
+    "http://localhost:4040".to_string()
+}
+
+/// Settings read from the client config file, e.g. `~/.config/chores/client.json`
+#[derive(Deserialize, Debug)]
+struct ClientConfig {
+    #[serde(default = "default_server_url")]
+    server_url: String,
+    /// Sent as a bearer token, for servers behind an authenticating proxy
+    token: Option<String>,
+    /// Household member that messages are posted and dismissed as
+    member: Option<String>,
+}
+
+impl Default for ClientConfig {
+    fn default() -> Self {
+        ClientConfig {
+            server_url: default_server_url(),
+            token: None,
+            member: None,
+        }
+    }
+}
+
+impl ClientConfig {
+    fn default_path() -> Option<PathBuf> {
+        let config_dir = match std::env::var_os("XDG_CONFIG_HOME") {
+            Some(dir) => PathBuf::from(dir),
+            None => PathBuf::from(std::env::var_os("HOME")?).join(".config"),
+        };
+
+        Some(config_dir.join("chores").join("client.json"))
+    }
+
+    fn load(path: Option<&str>) -> Result<Self> {
+        let path = match path {
+            Some(path) => PathBuf::from(path),
+            None => match Self::default_path() {
+                Some(path) if path.exists() => path,
+                _ => return Ok(ClientConfig::default()),
+            },
+        };
+
+        let contents = read_to_string(&path)
+            .map_err(|e| anyhow!("failed to read {}: {}", path.display(), e))?;
+
+        Ok(serde_json::from_str(&contents)?)
+    }
+}
+
+#[derive(Subcommand, Debug)]
+pub enum FlashCommand {
+    /// Post a new message
+    Add {
+        contents: String,
+        /// One of info, warning or urgent
+        #[arg(long, default_value = "info")]
+        priority: FlashPriority,
+        /// When to start showing the message, as epoch seconds or RFC 3339
+        #[arg(long)]
+        show_from: Option<String>,
+        /// When to stop showing the message, as epoch seconds or RFC 3339
+        #[arg(long)]
+        expires_at: Option<String>,
+        /// Defaults to the member from the client config
+        #[arg(long)]
+        author: Option<String>,
+        /// Household member to address the message to, who is the only one that can dismiss it
+        #[arg(long)]
+        to: Option<String>,
+    },
+    /// List unacknowledged messages
+    List,
+    /// List every message, including dismissed ones, newest first
+    History {
+        #[arg(long)]
+        limit: Option<u32>,
+        #[arg(long)]
+        offset: Option<u32>,
+    },
+    /// Acknowledge a message
+    Dismiss { id: i64 },
+    /// Delete a message outright, leaving no history
+    Delete { id: i64 },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum ClientCommand {
+    /// List the chores shown on the dashboard
+    List {
+        /// How many days back to include missed chores from
+        #[arg(long)]
+        lookback_days: Option<u32>,
+    },
+    /// Mark a chore as completed
+    Complete {
+        title: String,
+        /// Due time of the instance to complete, as epoch seconds or RFC 3339. Defaults to the
+        /// earliest assigned or overdue instance
+        #[arg(long)]
+        at: Option<String>,
+    },
+    /// Manage messages
+    #[command(subcommand)]
+    Flash(FlashCommand),
+    /// Show the latest observation for each METAR station
+    Weather,
+}
+
+#[derive(Args, Debug)]
+pub struct ClientArgs {
+    /// Client config file with the server URL and token
+    #[arg(long)]
+    client_config: Option<String>,
+    /// Server URL, overriding the client config
+    #[arg(long)]
+    server: Option<String>,
+    /// Household member to post and dismiss messages as, overriding the client config
+    #[arg(long)]
+    member: Option<String>,
+    #[arg(long, value_enum, default_value_t = OutputFormat::Table)]
+    format: OutputFormat,
+    #[command(subcommand)]
+    command: ClientCommand,
+}
+
+pub struct Client {
+    http: reqwest::Client,
+    server_url: String,
+    token: Option<String>,
+    member: Option<String>,
+}
+
+impl Client {
+    /// Builds a client from the client config file, with `server` and `member` overriding its
+    /// settings
+    pub fn connect(
+        client_config: Option<&str>,
+        server: Option<String>,
+        member: Option<String>,
+    ) -> Result<Self> {
+        let mut config = ClientConfig::load(client_config)?;
+        if let Some(server) = server {
+            config.server_url = server;
+        }
+        if let Some(member) = member {
+            config.member = Some(member);
+        }
+
+        Ok(Client {
+            http: reqwest::Client::new(),
+            server_url: config.server_url.trim_end_matches('/').to_string(),
+            token: config.token,
+            member: config.member,
+        })
+    }
+
+    pub fn member(&self) -> Option<&str> {
+        self.member.as_deref()
+    }
+
+    async fn send<T: DeserializeOwned>(&self, request: reqwest::RequestBuilder) -> Result<T> {
+        let request = match &self.token {
+            Some(token) => request.bearer_auth(token),
+            None => request,
+        };
+
+        let response = request.send().await?.error_for_status()?;
+
+        Ok(response.json().await?)
+    }
+
+    pub async fn get<T: DeserializeOwned>(
+        &self,
+        path: &str,
+        query: &[(&str, String)],
+    ) -> Result<T> {
+        let url = format!("{}{}", self.server_url, path);
+        self.send(self.http.get(url).query(query)).await
+    }
+
+    pub async fn post<P: Serialize, T: DeserializeOwned>(
+        &self,
+        path: &str,
+        params: &P,
+    ) -> Result<T> {
+        let url = format!("{}{}", self.server_url, path);
+        self.send(self.http.post(url).form(params)).await
+    }
+
+    pub async fn delete<T: DeserializeOwned>(&self, path: &str) -> Result<T> {
+        let url = format!("{}{}", self.server_url, path);
+        self.send(self.http.delete(url)).await
+    }
+}
+
+fn print_json<T: Serialize>(value: &T) -> Result<()> {
+    println!("{}", serde_json::to_string_pretty(value)?);
+
+    Ok(())
+}
+
+/// Renders when a chore is due in the offset the server gave it, which is the configured timezone's
+/// rather than the one this machine is in
+pub fn format_due(chore: &ApiChore) -> String {
+    match DateTime::parse_from_rfc3339(&chore.expected_completion_at) {
+        Ok(time) => time.format(TABLE_TIME_FORMAT).to_string(),
+        Err(_) => chore.expected_completion_at.clone(),
+    }
+}
+
+/// Renders a time the API only gives as epoch seconds, in UTC as it doesn't say the server's zone
+pub fn format_timestamp(timestamp: i64) -> String {
+    match Utc.timestamp_opt(timestamp, 0).single() {
+        Some(time) => format!("{} UTC", time.format(TABLE_TIME_FORMAT)),
+        None => timestamp.to_string(),
+    }
+}
+
+pub fn status_name(status: &ApiStatus) -> &'static str {
+    match status {
+        ApiStatus::Upcoming => "upcoming",
+        ApiStatus::Assigned => "assigned",
+        ApiStatus::Overdue => "overdue",
+        ApiStatus::Completed => "completed",
+        ApiStatus::Missed => "missed",
+        ApiStatus::Cancelled => "cancelled",
+    }
+}
+
+fn parse_at(value: &str) -> Result<i64> {
+    if let Ok(timestamp) = value.parse::<i64>() {
+        return Ok(timestamp);
+    }
+
+    DateTime::parse_from_rfc3339(value)
+        .map(|time| time.timestamp())
+        .map_err(|_| anyhow!("\"{}\" is neither epoch seconds nor RFC 3339", value))
+}
+
+pub async fn list_chores(client: &Client, lookback_days: Option<u32>) -> Result<Vec<ApiChore>> {
+    let mut query = Vec::new();
+    if let Some(days) = lookback_days {
+        query.push(("lookback_days", days.to_string()));
+    }
+
+    let response: ListChoresResponse = client.get("/api/chores", &query).await?;
+    if !response.success {
+        return Err(anyhow!(response.error.unwrap_or_default()));
+    }
+
+    Ok(response.chores)
+}
+
+async fn complete(
+    client: &Client,
+    format: OutputFormat,
+    title: String,
+    at: Option<String>,
+) -> Result<()> {
+    let at = at.as_deref().map(parse_at).transpose()?;
+
+    let chores = list_chores(client, None).await?;
+    let chore = chores
+        .into_iter()
+        .filter(|chore| {
+            chore.title.eq_ignore_ascii_case(&title)
+                || chore.display_name.eq_ignore_ascii_case(&title)
+        })
+        .filter(|chore| match at {
+            Some(at) => chore.expected_completion_time == at,
+            None => matches!(chore.status, ApiStatus::Assigned | ApiStatus::Overdue),
+        })
+        .min_by_key(|chore| chore.expected_completion_time)
+        .ok_or_else(|| anyhow!("no matching instance of \"{}\" to complete", title))?;
+
+    let response: CompleteChoreResponse = client
+        .post(
+            "/api/chores/complete",
+            &CompleteChoreParams {
+                id: Some(chore.id),
+                ..Default::default()
+            },
+        )
+        .await?;
+
+    match format {
+        OutputFormat::Json => print_json(&response)?,
+        OutputFormat::Table if response.success => println!(
+            "Completed \"{}\" due {}",
+            chore.display_name,
+            format_due(&chore)
+        ),
+        OutputFormat::Table => {}
+    }
+
+    match response.success {
+        true => Ok(()),
+        false => Err(anyhow!(response.error.unwrap_or_default())),
+    }
+}
+
+async fn flash(client: &Client, format: OutputFormat, command: FlashCommand) -> Result<()> {
+    match command {
+        FlashCommand::Add {
+            contents,
+            priority,
+            show_from,
+            expires_at,
+            author,
+            to,
+        } => {
+            let params = AddFlashParams {
+                contents,
+                priority,
+                show_from: show_from.as_deref().map(parse_at).transpose()?,
+                expires_at: expires_at.as_deref().map(parse_at).transpose()?,
+                author: author.or_else(|| client.member().map(str::to_string)),
+                recipient: to,
+            };
+            let response: AddFlashResponse = client.post("/api/flashes", &params).await?;
+
+            match (format, response.id) {
+                (OutputFormat::Json, _) => print_json(&response)?,
+                (OutputFormat::Table, Some(id)) => println!("Added message {}", id),
+                (OutputFormat::Table, None) => {}
+            }
+
+            match response.success {
+                true => Ok(()),
+                false => Err(anyhow!(response.error.unwrap_or_default())),
+            }
+        }
+        FlashCommand::List => {
+            let response: GetFlashResponse = client.get("/api/flashes", &[]).await?;
+            if !response.success {
+                return Err(anyhow!(response.error.unwrap_or_default()));
+            }
+
+            match format {
+                OutputFormat::Json => print_json(&response.flashes)?,
+                OutputFormat::Table => {
+                    let rows: Vec<[String; 6]> = response
+                        .flashes
+                        .iter()
+                        .map(|flash| {
+                            [
+                                flash.id.to_string(),
+                                flash.priority.as_str().to_string(),
+                                format_timestamp(flash.created_at),
+                                flash.author.clone().unwrap_or_default(),
+                                flash.recipient.clone().unwrap_or_default(),
+                                flash.contents.clone(),
+                            ]
+                        })
+                        .collect();
+                    table::print_table(
+                        ["ID", "PRIORITY", "CREATED", "AUTHOR", "FOR", "MESSAGE"],
+                        &rows,
+                    );
+                }
+            }
+
+            Ok(())
+        }
+        FlashCommand::History { limit, offset } => {
+            let mut query = Vec::new();
+            if let Some(limit) = limit {
+                query.push(("limit", limit.to_string()));
+            }
+            if let Some(offset) = offset {
+                query.push(("offset", offset.to_string()));
+            }
+
+            let response: FlashHistoryResponse = client.get("/api/flashes/history", &query).await?;
+            if !response.success {
+                return Err(anyhow!(response.error.unwrap_or_default()));
+            }
+
+            match format {
+                OutputFormat::Json => print_json(&response.flashes)?,
+                OutputFormat::Table => {
+                    let rows: Vec<[String; 5]> = response
+                        .flashes
+                        .iter()
+                        .map(|entry| {
+                            let dismissed = match (entry.acknowledged_at, &entry.acknowledged_by) {
+                                (Some(at), Some(by)) => {
+                                    format!("{} by {}", format_timestamp(at), by)
+                                }
+                                (Some(at), None) => format_timestamp(at),
+                                (None, _) if entry.acknowledged => "yes".to_string(),
+                                (None, _) => String::new(),
+                            };
+
+                            [
+                                entry.flash.id.to_string(),
+                                format_timestamp(entry.flash.created_at),
+                                entry.flash.author.clone().unwrap_or_default(),
+                                dismissed,
+                                entry.flash.contents.clone(),
+                            ]
+                        })
+                        .collect();
+                    table::print_table(["ID", "CREATED", "AUTHOR", "DISMISSED", "MESSAGE"], &rows);
+                    println!(
+                        "{} of {} message(s)",
+                        response.flashes.len(),
+                        response.total
+                    );
+                }
+            }
+
+            Ok(())
+        }
+        FlashCommand::Dismiss { id } => {
+            let params = DismissFlashParams {
+                id,
+                member: client.member().map(str::to_string),
+            };
+            let response: DismissFlashResponse =
+                client.post("/api/flashes/dismiss", &params).await?;
+
+            match format {
+                OutputFormat::Json => print_json(&response)?,
+                OutputFormat::Table if response.success => println!("Dismissed message {}", id),
+                OutputFormat::Table => {}
+            }
+
+            match response.success {
+                true => Ok(()),
+                false => Err(anyhow!(response.error.unwrap_or_default())),
+            }
+        }
+        FlashCommand::Delete { id } => {
+            let response: DeleteFlashResponse =
+                client.delete(&format!("/api/flashes/{}", id)).await?;
+
+            match format {
+                OutputFormat::Json => print_json(&response)?,
+                OutputFormat::Table if response.success => println!("Deleted message {}", id),
+                OutputFormat::Table => {}
+            }
+
+            match response.success {
+                true => Ok(()),
+                false => Err(anyhow!(response.error.unwrap_or_default())),
+            }
+        }
+    }
+}
+
+async fn weather(client: &Client, format: OutputFormat) -> Result<()> {
+    let response: GetMetarsResponse = client.get("/api/metars", &[]).await?;
+
+    if let OutputFormat::Json = format {
+        return print_json(&response.stations);
+    }
+
+    let mut stations: Vec<_> = response.stations.iter().collect();
+    stations.sort_by(|a, b| a.0.cmp(b.0));
+
+    let rows: Vec<[String; 6]> = stations
+        .into_iter()
+        .map(|(station, metar)| {
+            // observations cached before summaries were added don't have one
+            let weather = match metar.summary.is_empty() {
+                true => metar
+                    .weather
+                    .iter()
+                    .map(|weather| {
+                        format!("{} {}", weather.intensity, weather.conditions.join(" "))
+                    })
+                    .collect::<Vec<_>>()
+                    .join(", "),
+                false => metar.summary.clone(),
+            };
+
+            [
+                match metar.stale {
+                    true => format!("{} (stale)", station),
+                    false => station.clone(),
+                },
+                metar
+                    .temperature
+                    .map(|temperature| format!("{}°C", temperature))
+                    .unwrap_or_default(),
+                metar
+                    .pressure
+                    .map(|pressure| format!("{} hPa", pressure))
+                    .unwrap_or_default(),
+                metar
+                    .flight_category
+                    .map(|category| category.name().to_string())
+                    .unwrap_or_default(),
+                weather,
+                metar.metar.clone(),
+            ]
+        })
+        .collect();
+
+    table::print_table(
+        [
+            "STATION",
+            "TEMPERATURE",
+            "PRESSURE",
+            "CATEGORY",
+            "WEATHER",
+            "METAR",
+        ],
+        &rows,
+    );
+
+    Ok(())
+}
+
+pub async fn run(args: ClientArgs) -> Result<()> {
+    let client = Client::connect(args.client_config.as_deref(), args.server, args.member)?;
+
+    match args.command {
+        ClientCommand::List { lookback_days } => {
+            let chores = list_chores(&client, lookback_days).await?;
+
+            match args.format {
+                OutputFormat::Json => print_json(&chores)?,
+                OutputFormat::Table => {
+                    let rows: Vec<[String; 4]> = chores
+                        .iter()
+                        .map(|chore| {
+                            [
+                                chore.display_name.clone(),
+                                status_name(&chore.status).to_string(),
+                                format_due(chore),
+                                chore.schedule.clone(),
+                            ]
+                        })
+                        .collect();
+                    table::print_table(["CHORE", "STATUS", "DUE", "SCHEDULE"], &rows);
+                }
+            }
+
+            Ok(())
+        }
+        ClientCommand::Complete { title, at } => complete(&client, args.format, title, at).await,
+        ClientCommand::Flash(command) => flash(&client, args.format, command).await,
+        ClientCommand::Weather => weather(&client, args.format).await,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formats_due_times_in_the_servers_offset() {
+        let chore: ApiChore = serde_json::from_value(serde_json::json!({
+            "id": 1,
+            "title": "dishes",
+            "display_name": "Dishes",
+            "description": "",
+            "description_html": "",
+            "expected_completion_time": 1_792_382_400,
+            "expected_completion_at": "2026-10-18T22:00:00-06:00",
+            "schedule": "daily",
+            "status": "assigned",
+        }))
+        .unwrap();
+
+        assert_eq!(format_due(&chore), "Sun 2026-10-18 22:00");
+        assert_eq!(format_timestamp(1_792_382_400), "Mon 2026-10-19 04:00 UTC");
+    }
+}
@@ -0,0 +1,573 @@
+use std::collections::hash_map::DefaultHasher;
+use std::convert::Infallible;
+use std::hash::{Hash, Hasher};
+use std::sync::{Arc, Mutex};
+
+use anyhow::Result;
+use axum::body::{self, Bytes, Full};
+use axum::extract::Query;
+use axum::http::{header, HeaderMap, HeaderValue, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::Extension;
+use chores::api::{ApiChore, ApiStatus, Flash, FlashPriority, ListChoresParams};
+use chores::weather::StationMetar;
+use embedded_graphics::mono_font::iso_8859_1::{FONT_10X20, FONT_9X15};
+use embedded_graphics::mono_font::{MonoFont, MonoTextStyle, MonoTextStyleBuilder};
+use embedded_graphics::pixelcolor::Gray8;
+use embedded_graphics::prelude::*;
+use embedded_graphics::primitives::{Line, PrimitiveStyle, Rectangle};
+use embedded_graphics::text::{Baseline, Text};
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use utoipa::{IntoParams, ToSchema};
+
+use crate::error::ApiError;
+use crate::observations::cached_metars;
+use crate::{get_flashes_impl, list_chores_impl, Config};
+
+const MIN_DIMENSION: u32 = 64;
+const MAX_DIMENSION: u32 = 4096;
+/// How many sizes and palettes to keep a rendered image for
+const MAX_CACHED_IMAGES: usize = 4;
+const MARGIN: i32 = 8;
+
+/// Shade used for less important text, dark enough to stay black on a black and white display
+const DIM: Gray8 = Gray8::new(0x60);
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq, Hash, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum Palette {
+    /// 1-bit black and white
+    Bw,
+    /// 2-bit, four shades of gray
+    Gray4,
+    /// 8-bit grayscale
+    Gray,
+}
+
+/// Layout of `/api/dashboard.png`, with the size and palette overridable per request
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct DashboardImageConfig {
+    pub width: u32,
+    pub height: u32,
+    pub palette: Palette,
+    pub max_flashes: usize,
+    pub show_weather: bool,
+    pub show_upcoming: bool,
+    pub show_finished: bool,
+}
+
+impl Default for DashboardImageConfig {
+    fn default() -> Self {
+        DashboardImageConfig {
+            width: 800,
+            height: 480,
+            palette: Palette::Bw,
+            max_flashes: 3,
+            show_weather: true,
+            show_upcoming: true,
+            show_finished: true,
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct DashboardImageParams {
+    width: Option<u32>,
+    height: Option<u32>,
+    palette: Option<Palette>,
+}
+
+/// Width, height and palette of a rendered image
+type ImageKey = (u32, u32, Palette);
+
+/// The last image rendered for the most recently requested sizes and palettes, along with a hash of
+/// what it was rendered from. Any size can be requested, so only `MAX_CACHED_IMAGES` are kept
+#[derive(Default)]
+pub struct DashboardImageCache(Mutex<Vec<(ImageKey, u64, Bytes)>>);
+
+impl DashboardImageCache {
+    /// The cached image for `key`, if it was rendered from data with the same `hash`
+    fn get(&self, key: ImageKey, hash: u64) -> Option<Bytes> {
+        let images = self.0.lock().unwrap();
+        images
+            .iter()
+            .find(|(cached_key, cached_hash, _)| *cached_key == key && *cached_hash == hash)
+            .map(|(_, _, png)| png.clone())
+    }
+
+    /// Replaces the image for `key`, dropping the least recently rendered one when full
+    fn insert(&self, key: ImageKey, hash: u64, png: Bytes) {
+        let mut images = self.0.lock().unwrap();
+        images.retain(|(cached_key, _, _)| *cached_key != key);
+        images.insert(0, (key, hash, png));
+        images.truncate(MAX_CACHED_IMAGES);
+    }
+}
+
+struct Canvas {
+    width: u32,
+    height: u32,
+    pixels: Vec<u8>,
+}
+
+impl DrawTarget for Canvas {
+    type Color = Gray8;
+    type Error = Infallible;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        for Pixel(point, color) in pixels {
+            if point.x < 0 || point.y < 0 {
+                continue;
+            }
+
+            let (x, y) = (point.x as u32, point.y as u32);
+            if x < self.width && y < self.height {
+                self.pixels[(y * self.width + x) as usize] = color.luma();
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl OriginDimensions for Canvas {
+    fn size(&self) -> Size {
+        Size::new(self.width, self.height)
+    }
+}
+
+impl Canvas {
+    fn new(width: u32, height: u32) -> Self {
+        Canvas {
+            width,
+            height,
+            pixels: vec![0xff; (width * height) as usize],
+        }
+    }
+
+    fn text(&mut self, text: &str, x: i32, y: i32, style: MonoTextStyle<Gray8>) {
+        Text::with_baseline(text, Point::new(x, y), style, Baseline::Top)
+            .draw(self)
+            .unwrap();
+    }
+
+    fn fill(&mut self, x: i32, y: i32, width: u32, height: u32, color: Gray8) {
+        Rectangle::new(Point::new(x, y), Size::new(width, height))
+            .into_styled(PrimitiveStyle::with_fill(color))
+            .draw(self)
+            .unwrap();
+    }
+
+    fn outline(&mut self, x: i32, y: i32, width: u32, height: u32) {
+        Rectangle::new(Point::new(x, y), Size::new(width, height))
+            .into_styled(PrimitiveStyle::with_stroke(Gray8::BLACK, 2))
+            .draw(self)
+            .unwrap();
+    }
+
+    fn line(&mut self, from: Point, to: Point, color: Gray8) {
+        Line::new(from, to)
+            .into_styled(PrimitiveStyle::with_stroke(color, 2))
+            .draw(self)
+            .unwrap();
+    }
+
+    /// Packs the pixels into a PNG with the bit depth of `palette`
+    fn encode(&self, palette: Palette) -> Result<Vec<u8>> {
+        let depth: u32 = match palette {
+            Palette::Bw => 1,
+            Palette::Gray4 => 2,
+            Palette::Gray => 8,
+        };
+
+        let row_bytes = (self.width * depth).div_ceil(8) as usize;
+        let mut data = vec![0u8; row_bytes * self.height as usize];
+        for (y, row) in self.pixels.chunks(self.width as usize).enumerate() {
+            for (x, luma) in row.iter().enumerate() {
+                let value = match palette {
+                    // anything but near-white becomes black so that dim text stays legible
+                    Palette::Bw => (*luma >= 0xc0) as u8,
+                    Palette::Gray4 => luma >> 6,
+                    Palette::Gray => *luma,
+                };
+
+                let bit = x * depth as usize;
+                let shift = 8 - depth as usize - bit % 8;
+                data[y * row_bytes + bit / 8] |= value << shift;
+            }
+        }
+
+        let mut png = Vec::new();
+        let mut encoder = png::Encoder::new(&mut png, self.width, self.height);
+        encoder.set_color(png::ColorType::Grayscale);
+        encoder.set_depth(match palette {
+            Palette::Bw => png::BitDepth::One,
+            Palette::Gray4 => png::BitDepth::Two,
+            Palette::Gray => png::BitDepth::Eight,
+        });
+        encoder.write_header()?.write_image_data(&data)?;
+
+        Ok(png)
+    }
+}
+
+fn style(font: &'static MonoFont<'static>, color: Gray8) -> MonoTextStyle<'static, Gray8> {
+    MonoTextStyle::new(font, color)
+}
+
+/// Cuts `text` down to `width` characters, marking where it was cut
+fn truncate(text: &str, width: usize) -> String {
+    if text.chars().count() <= width {
+        return text.to_string();
+    }
+
+    let mut truncated: String = text.chars().take(width.saturating_sub(3)).collect();
+    truncated.push_str("...");
+    truncated
+}
+
+/// Word-wraps `text` to `width` characters, truncating the last of at most `max_lines` lines
+fn wrap(text: &str, width: usize, max_lines: usize) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    let mut current = String::new();
+
+    for word in text.split_whitespace() {
+        if !current.is_empty() && current.chars().count() + 1 + word.chars().count() > width {
+            lines.push(std::mem::take(&mut current));
+        }
+        if !current.is_empty() {
+            current.push(' ');
+        }
+        current.push_str(word);
+    }
+    if !current.is_empty() {
+        lines.push(current);
+    }
+
+    if lines.len() > max_lines {
+        let rest = lines.split_off(max_lines - 1).join(" ");
+        lines.push(rest);
+    }
+
+    lines
+        .into_iter()
+        .map(|line| truncate(&line, width))
+        .collect()
+}
+
+struct DashboardData {
+    date: String,
+    chores: Vec<ApiChore>,
+    flashes: Vec<Flash>,
+    metars: Vec<(String, StationMetar)>,
+}
+
+fn render(data: &DashboardData, options: &DashboardImageConfig, config: &Config) -> Canvas {
+    let mut canvas = Canvas::new(options.width, options.height);
+    let width = options.width as i32;
+    let height = options.height as i32;
+
+    let large_width = FONT_10X20.character_size.width as i32;
+    let large_height = FONT_10X20.character_size.height as i32;
+    let small_width = FONT_9X15.character_size.width as i32;
+    let small_height = FONT_9X15.character_size.height as i32;
+
+    // header bar
+    let header_height = large_height + 8;
+    canvas.fill(0, 0, options.width, header_height as u32, Gray8::BLACK);
+    let header_style = style(&FONT_10X20, Gray8::WHITE);
+    canvas.text("Chores", MARGIN, 4, header_style);
+    let date_x = width - MARGIN - data.date.chars().count() as i32 * large_width;
+    canvas.text(&data.date, date_x, 4, header_style);
+
+    let mut y = header_height + MARGIN;
+    let columns = ((width - 4 * MARGIN) / small_width).max(1) as usize;
+
+    for flash in data.flashes.iter().take(options.max_flashes) {
+        let text = match &flash.recipient {
+            Some(recipient) => format!("{}: {}", recipient, flash.contents),
+            None => flash.contents.clone(),
+        };
+        let lines = wrap(&text, columns, 3);
+        let box_height = lines.len() as i32 * small_height + 2 * MARGIN;
+        if y + box_height > height {
+            break;
+        }
+
+        canvas.outline(MARGIN, y, (width - 2 * MARGIN) as u32, box_height as u32);
+        if flash.priority == FlashPriority::Urgent {
+            canvas.outline(
+                MARGIN + 1,
+                y + 1,
+                (width - 2 * MARGIN - 2) as u32,
+                (box_height - 2) as u32,
+            );
+        }
+        for (i, line) in lines.iter().enumerate() {
+            let line_y = y + MARGIN + i as i32 * small_height;
+            canvas.text(line, 2 * MARGIN, line_y, style(&FONT_9X15, Gray8::BLACK));
+        }
+        y += box_height + MARGIN;
+    }
+
+    if options.show_weather {
+        for (station, metar) in data.metars.iter() {
+            if y + small_height > height {
+                break;
+            }
+
+            let mut summary = vec![station.clone()];
+            if !metar.summary.is_empty() {
+                summary.push(metar.summary.clone());
+            } else if let Some(temperature) = metar.temperature {
+                summary.push(format!("{}\u{b0}C", temperature));
+            }
+            if let Some(pressure) = metar.pressure {
+                summary.push(format!("{} hPa", pressure));
+            }
+            if metar.summary.is_empty() {
+                for weather in metar.weather.iter() {
+                    summary.push(format!(
+                        "{} {}",
+                        weather.intensity,
+                        weather.conditions.join(" ")
+                    ));
+                }
+            }
+
+            let text = truncate(&summary.join("  "), columns);
+            canvas.text(&text, MARGIN, y, style(&FONT_9X15, Gray8::BLACK));
+            y += small_height + 2;
+        }
+        y += MARGIN / 2;
+    }
+
+    canvas.line(
+        Point::new(MARGIN, y),
+        Point::new(width - MARGIN, y),
+        Gray8::BLACK,
+    );
+    y += MARGIN;
+
+    let chores: Vec<&ApiChore> = data
+        .chores
+        .iter()
+        .filter(|chore| match chore.status {
+            ApiStatus::Upcoming => options.show_upcoming,
+            ApiStatus::Completed | ApiStatus::Missed | ApiStatus::Cancelled => {
+                options.show_finished
+            }
+            ApiStatus::Overdue | ApiStatus::Assigned => true,
+        })
+        .collect();
+
+    let row_height = large_height + 4;
+    let label_columns = 9;
+    let title_x = MARGIN + (label_columns + 1) * large_width;
+    let time_columns = 9;
+    let title_columns =
+        ((width - title_x - MARGIN) / large_width - time_columns - 1).max(1) as usize;
+
+    for (i, chore) in chores.iter().enumerate() {
+        let remaining = chores.len() - i;
+        // leave room for the "more" line unless this is the last chore
+        let needed = match remaining {
+            1 => row_height,
+            _ => row_height + small_height,
+        };
+        if y + needed > height {
+            let more = format!("+{} more", remaining);
+            canvas.text(&more, MARGIN, y, style(&FONT_9X15, Gray8::BLACK));
+            break;
+        }
+
+        let (label, label_style, title_color) = match chore.status {
+            ApiStatus::Overdue => (
+                "OVERDUE",
+                MonoTextStyleBuilder::new()
+                    .font(&FONT_10X20)
+                    .text_color(Gray8::WHITE)
+                    .background_color(Gray8::BLACK)
+                    .build(),
+                Gray8::BLACK,
+            ),
+            ApiStatus::Assigned => ("TODO", style(&FONT_10X20, Gray8::BLACK), Gray8::BLACK),
+            ApiStatus::Upcoming => ("LATER", style(&FONT_10X20, DIM), DIM),
+            ApiStatus::Completed => ("DONE", style(&FONT_10X20, DIM), DIM),
+            ApiStatus::Missed => ("MISSED", style(&FONT_10X20, DIM), DIM),
+            ApiStatus::Cancelled => ("CANCEL", style(&FONT_10X20, DIM), DIM),
+        };
+
+        canvas.text(&format!(" {:<7} ", label), MARGIN, y, label_style);
+
+        let title = truncate(&chore.display_name, title_columns);
+        canvas.text(&title, title_x, y, style(&FONT_10X20, title_color));
+        if let ApiStatus::Completed | ApiStatus::Missed | ApiStatus::Cancelled = chore.status {
+            let strike_y = y + large_height / 2;
+            let strike_end = title_x + title.chars().count() as i32 * large_width;
+            canvas.line(
+                Point::new(title_x, strike_y),
+                Point::new(strike_end, strike_y),
+                title_color,
+            );
+        }
+
+        if let Ok(due) = config.localize_timestamp(chore.expected_completion_time) {
+            let due = due.format("%a %H:%M").to_string();
+            let due_x = width - MARGIN - due.chars().count() as i32 * large_width;
+            canvas.text(&due, due_x, y, style(&FONT_10X20, title_color));
+        }
+
+        y += row_height;
+    }
+
+    canvas
+}
+
+async fn fetch_data(pool: Arc<SqlitePool>, config: Arc<Config>) -> Result<DashboardData> {
+    let params = ListChoresParams {
+        lookback_days: None,
+    };
+    let mut chores = list_chores_impl(params, pool.clone(), config.clone()).await?;
+    chores.sort_by_key(|chore| (chore.status.priority(), chore.expected_completion_time));
+
+    let flashes = get_flashes_impl(pool.clone()).await?;
+
+    let mut metars: Vec<_> = cached_metars(&pool, &config).await?.into_iter().collect();
+    metars.sort_by(|a, b| a.0.cmp(&b.0));
+
+    Ok(DashboardData {
+        date: config.now().format("%a %b %-d").to_string(),
+        chores,
+        flashes,
+        metars,
+    })
+}
+
+/// Hash of everything that ends up in the image, so an unchanged dashboard isn't re-rendered
+fn data_hash(data: &DashboardData, options: &DashboardImageConfig) -> Result<u64> {
+    let mut hasher = DefaultHasher::new();
+    serde_json::to_vec(&(&data.date, &data.chores, &data.flashes, &data.metars))?.hash(&mut hasher);
+    format!("{:?}", options).hash(&mut hasher);
+
+    Ok(hasher.finish())
+}
+
+async fn dashboard_png_impl(
+    params: DashboardImageParams,
+    pool: Arc<SqlitePool>,
+    config: Arc<Config>,
+    cache: Arc<DashboardImageCache>,
+) -> Result<(u64, Bytes)> {
+    let mut options = config.dashboard_image.clone();
+    options.width = params
+        .width
+        .unwrap_or(options.width)
+        .clamp(MIN_DIMENSION, MAX_DIMENSION);
+    options.height = params
+        .height
+        .unwrap_or(options.height)
+        .clamp(MIN_DIMENSION, MAX_DIMENSION);
+    options.palette = params.palette.unwrap_or(options.palette);
+
+    let data = fetch_data(pool, config.clone()).await?;
+    let hash = data_hash(&data, &options)?;
+    let key = (options.width, options.height, options.palette);
+
+    if let Some(png) = cache.get(key, hash) {
+        return Ok((hash, png));
+    }
+
+    tracing::debug!(
+        "rendering {}x{} dashboard image",
+        options.width,
+        options.height
+    );
+    let png = Bytes::from(render(&data, &options, &config).encode(options.palette)?);
+    cache.insert(key, hash, png.clone());
+
+    Ok((hash, png))
+}
+
+/// The dashboard rendered for an e-paper display, defaulting to the configured size and palette
+#[utoipa::path(
+    get,
+    path = "/api/dashboard.png",
+    tag = "v1",
+    params(DashboardImageParams),
+    responses(
+        (status = 200, description = "The rendered image", content_type = "image/png"),
+        (status = 304, description = "Unchanged since the image with the `If-None-Match` ETag")
+    )
+)]
+pub async fn dashboard_png(
+    Query(params): Query<DashboardImageParams>,
+    headers: HeaderMap,
+    Extension(pool): Extension<Arc<SqlitePool>>,
+    Extension(config): Extension<Arc<Config>>,
+    Extension(cache): Extension<Arc<DashboardImageCache>>,
+) -> Response {
+    let (hash, png) = match dashboard_png_impl(params, pool, config, cache).await {
+        Ok(image) => image,
+        Err(e) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("failed to render dashboard: {}", ApiError::from(e)),
+            )
+                .into_response()
+        }
+    };
+
+    let etag = format!("\"{:016x}\"", hash);
+    let not_modified = headers
+        .get(header::IF_NONE_MATCH)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.split(',').any(|tag| tag.trim() == etag));
+
+    let response = Response::builder()
+        .header(header::ETAG, HeaderValue::from_str(&etag).unwrap())
+        .header(header::CACHE_CONTROL, "no-cache");
+
+    match not_modified {
+        true => response
+            .status(StatusCode::NOT_MODIFIED)
+            .body(body::boxed(body::Empty::new()))
+            .unwrap(),
+        false => response
+            .status(StatusCode::OK)
+            .header(header::CONTENT_TYPE, HeaderValue::from_static("image/png"))
+            .body(body::boxed(Full::from(png)))
+            .unwrap(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cache_keeps_recent_sizes() {
+        let cache = DashboardImageCache::default();
+        let png = |n: u8| Bytes::from(vec![n]);
+
+        cache.insert((800, 480, Palette::Bw), 1, png(1));
+        cache.insert((800, 480, Palette::Bw), 2, png(2));
+        assert_eq!(cache.get((800, 480, Palette::Bw), 1), None);
+        assert_eq!(cache.get((800, 480, Palette::Bw), 2), Some(png(2)));
+        assert_eq!(cache.0.lock().unwrap().len(), 1);
+
+        for width in 100..110 {
+            cache.insert((width, 480, Palette::Gray), 3, png(3));
+        }
+        assert_eq!(cache.0.lock().unwrap().len(), MAX_CACHED_IMAGES);
+        assert_eq!(cache.get((800, 480, Palette::Bw), 2), None);
+        assert_eq!(cache.get((109, 480, Palette::Gray), 3), Some(png(3)));
+        assert_eq!(cache.get((100, 480, Palette::Gray), 3), None);
+    }
+}
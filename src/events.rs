@@ -0,0 +1,199 @@
+use std::collections::{HashMap, VecDeque};
+use std::convert::Infallible;
+use std::sync::{Arc, Mutex};
+
+use axum::extract::Query;
+use axum::http::HeaderMap;
+use axum::response::sse::{Event, KeepAlive, Sse};
+use axum::Extension;
+use chores::api::ApiEvent;
+use chores::weather::StationMetar;
+use chrono::Utc;
+use futures::stream::{self, Stream, StreamExt};
+use serde::Deserialize;
+use tokio::sync::broadcast::{self, error::RecvError};
+use utoipa::IntoParams;
+
+/// How many past events are kept for clients reconnecting with a last event ID
+const HISTORY_LEN: usize = 256;
+
+struct History {
+    next_id: u64,
+    events: VecDeque<(u64, ApiEvent)>,
+    /// Serialized stations from the last weather update, to only announce changes
+    last_weather: Option<String>,
+}
+
+struct Subscription {
+    replay: Vec<(u64, ApiEvent)>,
+    /// Whether some of the events after the last ID have already been forgotten
+    gap: bool,
+    receiver: broadcast::Receiver<(u64, ApiEvent)>,
+}
+
+/// Fans out changes to every `/api/events` subscriber
+pub struct EventBus {
+    sender: broadcast::Sender<(u64, ApiEvent)>,
+    history: Mutex<History>,
+}
+
+impl EventBus {
+    pub fn new() -> Arc<Self> {
+        let (sender, _) = broadcast::channel(HISTORY_LEN);
+
+        Arc::new(EventBus {
+            sender,
+            history: Mutex::new(History {
+                // start from the current time so IDs from before a restart are never reused
+                next_id: Utc::now().timestamp_micros() as u64,
+                events: VecDeque::with_capacity(HISTORY_LEN),
+                last_weather: None,
+            }),
+        })
+    }
+
+    pub fn publish(&self, event: ApiEvent) {
+        let mut history = self.history.lock().unwrap();
+
+        let id = history.next_id;
+        history.next_id += 1;
+
+        if history.events.len() == HISTORY_LEN {
+            history.events.pop_front();
+        }
+        history.events.push_back((id, event.clone()));
+
+        // there being no subscribers isn't an error
+        let _ = self.sender.send((id, event));
+    }
+
+    /// Publishes a weather update if the stations changed since the last one
+    pub fn weather_fetched(&self, stations: &HashMap<String, StationMetar>) {
+        let mut sorted: Vec<_> = stations.iter().collect();
+        sorted.sort_by(|a, b| a.0.cmp(b.0));
+        let serialized = serde_json::to_string(&sorted).unwrap_or_default();
+
+        {
+            let mut history = self.history.lock().unwrap();
+            if history.last_weather.as_ref() == Some(&serialized) {
+                return;
+            }
+            history.last_weather = Some(serialized);
+        }
+
+        self.publish(ApiEvent::WeatherUpdated {
+            stations: stations.clone(),
+        });
+    }
+
+    /// Receives events as they're published, for listeners within the server
+    pub fn listen(&self) -> broadcast::Receiver<(u64, ApiEvent)> {
+        self.sender.subscribe()
+    }
+
+    /// Subscribes to new events, along with the events after `last_id` that can be replayed
+    fn subscribe(&self, last_id: Option<u64>) -> Subscription {
+        // holding the lock keeps anything from being published between the replay and the receiver
+        let history = self.history.lock().unwrap();
+        let receiver = self.sender.subscribe();
+
+        let last_id = match last_id {
+            Some(last_id) => last_id,
+            None => {
+                return Subscription {
+                    replay: Vec::new(),
+                    gap: false,
+                    receiver,
+                }
+            }
+        };
+
+        let oldest = match history.events.front() {
+            Some((id, _)) => *id,
+            None => history.next_id,
+        };
+        let gap = last_id.saturating_add(1) < oldest || last_id >= history.next_id;
+
+        let replay = history
+            .events
+            .iter()
+            .filter(|(id, _)| *id > last_id)
+            .cloned()
+            .collect();
+
+        Subscription {
+            replay,
+            gap,
+            receiver,
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct EventsParams {
+    /// For clients that can't set the `Last-Event-ID` header
+    last_event_id: Option<u64>,
+}
+
+fn to_sse((id, event): (u64, ApiEvent)) -> Event {
+    Event::default()
+        .id(id.to_string())
+        .event(event.name())
+        .data(serde_json::to_string(&event).unwrap_or_default())
+}
+
+/// Tells the client that events were missed, so it should fetch everything again
+fn resync() -> Event {
+    Event::default().event("resync").data("{}")
+}
+
+/// Stream of changes as Server-Sent Events, each named after its `type`
+#[utoipa::path(
+    get,
+    path = "/api/events",
+    tag = "v1",
+    params(
+        EventsParams,
+        ("Last-Event-ID" = Option<u64>, Header, description = "ID of the last event received, to replay those missed since")
+    ),
+    responses((status = 200, content_type = "text/event-stream", body = ApiEvent))
+)]
+pub async fn events(
+    Query(params): Query<EventsParams>,
+    headers: HeaderMap,
+    Extension(bus): Extension<Arc<EventBus>>,
+) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
+    let last_id = headers
+        .get("last-event-id")
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.parse().ok())
+        .or(params.last_event_id);
+
+    let Subscription {
+        replay,
+        gap,
+        receiver,
+    } = bus.subscribe(last_id);
+    let after = replay.last().map(|(id, _)| *id).or(last_id).unwrap_or(0);
+
+    let backlog = gap
+        .then(resync)
+        .into_iter()
+        .chain(replay.into_iter().map(to_sse));
+
+    let live = stream::unfold((receiver, after), |(mut receiver, after)| async move {
+        loop {
+            match receiver.recv().await {
+                Ok((id, _)) if id <= after => continue,
+                Ok((id, event)) => return Some((to_sse((id, event)), (receiver, id))),
+                Err(RecvError::Lagged(_)) => return Some((resync(), (receiver, after))),
+                Err(RecvError::Closed) => return None,
+            }
+        }
+    });
+
+    let stream = stream::iter(backlog).chain(live).map(Ok);
+
+    Sse::new(stream).keep_alive(KeepAlive::default())
+}
@@ -0,0 +1,374 @@
+use std::fmt::Write as _;
+use std::sync::Arc;
+
+use axum::extract::Query;
+use axum::http::StatusCode;
+use axum::response::{Html, IntoResponse, Redirect, Response};
+use axum::Extension;
+use chores::api::{ApiChore, ApiStatus, Flash, FlashPriority, ListChoresParams};
+use chores::weather::StationMetar;
+use serde::Deserialize;
+use sqlx::SqlitePool;
+use utoipa::IntoParams;
+
+use crate::client::status_name;
+use crate::error::ApiError;
+use crate::observations::cached_metars;
+use crate::{get_flashes_impl, list_chores_impl, Config};
+
+const DASHBOARD_PATH: &str = "/html";
+const DEFAULT_REFRESH_SECS: u64 = 60;
+const TIME_FORMAT: &str = "%a %b %-d, %H:%M";
+
+const STYLE: &str = "
+body { font-family: sans-serif; font-size: 1.4em; margin: 0.5em; color: #000; background: #fff; }
+h1 { font-size: 1.6em; margin: 0.2em 0; }
+.card { border: 3px solid #000; margin: 0.5em 0; padding: 0.4em 0.6em; }
+.card h2 { font-size: 1.3em; margin: 0 0 0.3em 0; }
+.card p, .markdown ul, .markdown ol, .markdown pre { margin: 0.2em 0; }
+.overdue, .urgent { border-width: 6px; }
+.warning { border-width: 4px; }
+.upcoming, .completed, .missed, .cancelled { border-style: dashed; }
+.error { border-style: double; border-width: 6px; }
+.small { font-size: 0.8em; }
+button { font-size: 1em; width: 100%; padding: 0.4em; margin-top: 0.3em; }
+input[type=text] { font-size: 1em; width: 100%; box-sizing: border-box; }
+";
+
+/// Where to send a form POST back to once it has been handled, instead of returning JSON
+#[derive(Deserialize, Debug, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct RedirectParams {
+    /// Local path to redirect to with a 303 instead of responding with JSON
+    redirect: Option<String>,
+}
+
+impl RedirectParams {
+    /// Rejects a redirect that would leave this server. Checked before the request is acted on, so
+    /// a form with a bad redirect doesn't change anything
+    pub fn reject_invalid(&self) -> Option<Response> {
+        match self.redirect.as_deref() {
+            Some(location) if !is_local_path(location) => {
+                Some((StatusCode::BAD_REQUEST, "Invalid redirect").into_response())
+            }
+            _ => None,
+        }
+    }
+
+    /// Responds with a redirect when one was requested, passing any error along for display
+    pub fn respond(&self, error: Option<String>) -> Option<Response> {
+        if let Some(rejection) = self.reject_invalid() {
+            return Some(rejection);
+        }
+        let location = self.redirect.as_deref()?;
+
+        let location = match error {
+            Some(error) => {
+                let separator = if location.contains('?') { '&' } else { '?' };
+                let query = serde_urlencoded::to_string([("error", error)]).unwrap();
+                format!("{}{}{}", location, separator, query)
+            }
+            None => location.to_string(),
+        };
+
+        Some(Redirect::to(&location).into_response())
+    }
+}
+
+/// Whether `location` stays within this server. Browsers treat `\\` like `/`, so `/\\host` is as
+/// protocol-relative as `//host`, and they strip tabs and newlines before resolving
+fn is_local_path(location: &str) -> bool {
+    location.starts_with('/')
+        && !location[1..].starts_with('/')
+        && !location.contains(|c: char| c == '\\' || c.is_control())
+}
+
+#[derive(Deserialize, Debug)]
+pub struct DashboardParams {
+    refresh: Option<u64>,
+    error: Option<String>,
+}
+
+pub fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            c => escaped.push(c),
+        }
+    }
+
+    escaped
+}
+
+fn format_time(config: &Config, timestamp: i64) -> String {
+    match config.localize_timestamp(timestamp) {
+        Ok(time) => time.format(TIME_FORMAT).to_string(),
+        Err(_) => timestamp.to_string(),
+    }
+}
+
+/// Action URL for a form that posts to `path` and comes back to the dashboard
+fn form_action(path: &str, back: &str) -> String {
+    let query = serde_urlencoded::to_string([("redirect", back)]).unwrap();
+    escape(&format!("{}?{}", path, query))
+}
+
+fn render_flash(html: &mut String, config: &Config, flash: &Flash, back: &str) {
+    let heading = match flash.priority {
+        FlashPriority::Urgent => "Urgent",
+        FlashPriority::Warning => "Warning",
+        FlashPriority::Info => "Message",
+    };
+    let author = match &flash.author {
+        Some(author) => format!(" by {}", escape(author)),
+        None => String::new(),
+    };
+    // only the recipient can dismiss a flash addressed to them, so ask who's dismissing it
+    let (recipient, member) = match &flash.recipient {
+        Some(recipient) => (
+            format!(" for {}", escape(recipient)),
+            "<input type=\"text\" name=\"member\" placeholder=\"Your name\" required>",
+        ),
+        None => (String::new(), ""),
+    };
+
+    write!(
+        html,
+        "<div class=\"card {}\"><h2>{}</h2><div class=\"markdown\">{}</div>\
+         <p class=\"small\">Created at {}{}{}</p>\
+         <form method=\"post\" action=\"{}\"><input type=\"hidden\" name=\"id\" value=\"{}\">\
+         {}<button type=\"submit\">Dismiss</button></form></div>",
+        flash.priority.as_str(),
+        heading,
+        flash.contents_html,
+        format_time(config, flash.created_at),
+        author,
+        recipient,
+        form_action("/api/flashes/dismiss", back),
+        flash.id,
+        member,
+    )
+    .unwrap();
+}
+
+fn render_metar(html: &mut String, station: &str, metar: &StationMetar) {
+    write!(html, "<div class=\"card\"><h2>{}</h2>", escape(station)).unwrap();
+    if !metar.summary.is_empty() {
+        write!(html, "<p>{}", escape(&metar.summary)).unwrap();
+        if let Some(category) = metar.flight_category {
+            write!(html, " ({})", category.name()).unwrap();
+        }
+        html.push_str("</p>");
+    }
+    if let Some(temperature) = metar.temperature {
+        write!(html, "<p>Temperature: {}&deg;C</p>", temperature).unwrap();
+    }
+    if let Some(pressure) = metar.pressure {
+        write!(html, "<p>Pressure: {} hPa</p>", pressure).unwrap();
+    }
+    if metar.stale {
+        html.push_str("<p class=\"small\">Out of date, the station hasn't been reachable</p>");
+    }
+    write!(
+        html,
+        "<p class=\"small\">{}</p></div>",
+        escape(&metar.metar)
+    )
+    .unwrap();
+}
+
+fn render_chore(html: &mut String, config: &Config, chore: &ApiChore, back: &str) {
+    let status = status_name(&chore.status);
+    let title = match chore.status {
+        ApiStatus::Completed | ApiStatus::Missed | ApiStatus::Cancelled => {
+            format!("<s>{}</s>", escape(&chore.display_name))
+        }
+        _ => escape(&chore.display_name),
+    };
+
+    write!(
+        html,
+        "<div class=\"card {}\"><h2>{}</h2><p>Status: {}</p><p><strong>Due: {}</strong></p>\
+         <div class=\"markdown\">{}</div>",
+        status,
+        title,
+        status,
+        format_time(config, chore.expected_completion_time),
+        chore.description_html,
+    )
+    .unwrap();
+
+    if matches!(chore.status, ApiStatus::Assigned | ApiStatus::Overdue) {
+        write!(
+            html,
+            "<form method=\"post\" action=\"{}\"><input type=\"hidden\" name=\"id\" value=\"{}\">\
+             <button type=\"submit\">Mark Completed</button></form>",
+            form_action("/api/chores/complete", back),
+            chore.id,
+        )
+        .unwrap();
+    }
+
+    html.push_str("</div>");
+}
+
+/// Dashboard rendered entirely on the server, for browsers that can't run the frontend script
+pub async fn dashboard(
+    Query(params): Query<DashboardParams>,
+    Extension(pool): Extension<Arc<SqlitePool>>,
+    Extension(config): Extension<Arc<Config>>,
+) -> Html<String> {
+    let refresh = params.refresh.unwrap_or(DEFAULT_REFRESH_SECS).max(1);
+    let back = format!("{}?refresh={}", DASHBOARD_PATH, refresh);
+
+    let mut errors: Vec<String> = params.error.into_iter().collect();
+
+    let params = ListChoresParams {
+        lookback_days: None,
+    };
+    let mut chores = match list_chores_impl(params, pool.clone(), config.clone()).await {
+        Ok(chores) => chores,
+        Err(e) => {
+            errors.push(format!("failed to fetch chores: {}", ApiError::from(e)));
+            Vec::new()
+        }
+    };
+    chores.sort_by_key(|chore| (chore.status.priority(), chore.expected_completion_time));
+
+    let flashes = match get_flashes_impl(pool.clone()).await {
+        Ok(flashes) => flashes,
+        Err(e) => {
+            errors.push(format!("failed to fetch flashes: {}", ApiError::from(e)));
+            Vec::new()
+        }
+    };
+
+    let mut metars: Vec<_> = match cached_metars(&pool, &config).await {
+        Ok(metars) => metars.into_iter().collect(),
+        Err(e) => {
+            errors.push(format!("failed to fetch weather: {}", ApiError::from(e)));
+            Vec::new()
+        }
+    };
+    metars.sort_by(|a, b| a.0.cmp(&b.0));
+
+    let mut html = String::new();
+    write!(
+        html,
+        "<!doctype html><html lang=\"en\"><head><meta charset=\"utf-8\">\
+         <meta http-equiv=\"refresh\" content=\"{}; url={}\">\
+         <meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\
+         <title>Chores List</title><style>{}</style></head><body><h1>Chores List</h1>",
+        refresh,
+        escape(&back),
+        STYLE,
+    )
+    .unwrap();
+
+    for error in errors.iter() {
+        write!(
+            html,
+            "<div class=\"card error\"><p>{}</p></div>",
+            escape(error)
+        )
+        .unwrap();
+    }
+
+    for flash in flashes.iter() {
+        render_flash(&mut html, &config, flash, &back);
+    }
+
+    for (station, metar) in metars.iter() {
+        render_metar(&mut html, station, metar);
+    }
+
+    for chore in chores.iter() {
+        render_chore(&mut html, &config, chore, &back);
+    }
+
+    write!(
+        html,
+        "<div class=\"card\"><h2>Add new message</h2><form method=\"post\" action=\"{}\">\
+         <input type=\"text\" name=\"contents\" placeholder=\"Message...\">\
+         <button type=\"submit\">Add</button></form></div>\
+         <p class=\"small\">Updated {}</p></body></html>",
+        form_action("/api/flashes", &back),
+        config.now().format(TIME_FORMAT),
+    )
+    .unwrap();
+
+    Html(html)
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::header::LOCATION;
+
+    use super::*;
+
+    fn respond(redirect: &str, error: Option<&str>) -> Response {
+        let params = RedirectParams {
+            redirect: Some(redirect.to_string()),
+        };
+        params.respond(error.map(str::to_string)).unwrap()
+    }
+
+    #[test]
+    fn redirects_within_this_server() {
+        let cases = [
+            ("/", None, "/"),
+            ("/html", None, "/html"),
+            ("/html?refresh=30", None, "/html?refresh=30"),
+            ("/html", Some("Not found"), "/html?error=Not+found"),
+            (
+                "/html?refresh=30",
+                Some("a&b"),
+                "/html?refresh=30&error=a%26b",
+            ),
+        ];
+
+        for (redirect, error, expected) in cases {
+            let response = respond(redirect, error);
+            assert_eq!(response.status(), StatusCode::SEE_OTHER, "{}", redirect);
+            assert_eq!(response.headers()[LOCATION], expected, "{}", redirect);
+        }
+    }
+
+    #[test]
+    fn rejects_other_locations() {
+        let cases = [
+            "",
+            "html",
+            "https://evil.example",
+            "//evil.example",
+            "/\\evil.example",
+            "/html\\..\\",
+            "/\t/evil.example",
+            "/html\r\nSet-Cookie: a=b",
+            "/html\0",
+        ];
+
+        for redirect in cases {
+            let response = respond(redirect, None);
+            assert_eq!(response.status(), StatusCode::BAD_REQUEST, "{:?}", redirect);
+            assert!(!response.headers().contains_key(LOCATION), "{:?}", redirect);
+
+            let params = RedirectParams {
+                redirect: Some(redirect.to_string()),
+            };
+            assert!(params.reject_invalid().is_some(), "{:?}", redirect);
+        }
+    }
+
+    #[test]
+    fn no_redirect_requested() {
+        let params = RedirectParams { redirect: None };
+        assert!(params.reject_invalid().is_none());
+        assert!(params.respond(Some("error".to_string())).is_none());
+    }
+}
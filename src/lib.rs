@@ -0,0 +1,5 @@
+//! Types shared between the chores server and its clients
+
+pub mod api;
+pub mod weather;
+pub mod weather_source;
@@ -1,37 +1,98 @@
-mod weather;
+mod alerts;
+mod assets;
+mod catch_up;
+mod check;
+mod client;
+mod dashboard_image;
+mod error;
+mod events;
+mod html;
+mod markdown;
+mod observations;
+mod openapi;
+mod preview;
+mod schedule;
+mod table;
+mod tui;
+mod v2;
 
 use std::collections::HashMap;
 use std::fs::read_to_string;
 use std::net::SocketAddr;
+use std::path::PathBuf;
 use std::str::FromStr;
 use std::sync::Arc;
 use std::time::Duration as StdDuration;
 
 use anyhow::{anyhow, Result};
-use axum::body;
-use axum::body::Full;
-use axum::extract::{Form, Query};
-use axum::http::{header, HeaderValue, Method, StatusCode};
+use axum::extract::{Form, Path, Query};
+use axum::handler::Handler;
+use axum::http::Method;
 use axum::response::{IntoResponse, Response};
-use axum::routing::{get, get_service, post};
+use axum::routing::{delete, get, post, put};
 use axum::{Extension, Json, Router};
-use chrono::{Datelike, Duration, Local, TimeZone};
-use clap::Parser;
-use cron::Schedule;
+use chrono::{DateTime, Duration, LocalResult, SecondsFormat, TimeZone, Utc};
+use chrono_tz::Tz;
+use clap::{Parser, Subcommand, ValueEnum};
 use serde::{Deserialize, Serialize};
-use sqlx::{Acquire, Row, SqlitePool};
+use sqlx::sqlite::SqliteRow;
+use sqlx::{Acquire, Row, Sqlite, SqlitePool, Transaction};
+use tokio::sync::RwLock;
 use tokio::try_join;
+use tower_http::compression::predicate::{DefaultPredicate, NotForContentType, Predicate};
+use tower_http::compression::CompressionLayer;
 use tower_http::cors::{Any, CorsLayer};
-use tower_http::services::ServeDir;
-
-use crate::weather::{build_metar_response, StationMetar};
-
-const INDEX_PATH: &'static str = "./assets/html/index.html";
+use utoipa::ToSchema;
+
+use chores::api::{
+    AddFlashParams, AddFlashResponse, ApiChore, ApiErrorResponse, ApiEvent, ApiStatus,
+    ArchivedChore, ArchivedChoreInstance, ChoreInstance, CompleteChoreParams,
+    CompleteChoreResponse, DeleteFlashResponse, DismissFlashParams, DismissFlashResponse, Flash,
+    FlashHistoryEntry, FlashHistoryPage, FlashHistoryParams, FlashHistoryResponse, FlashPriority,
+    GetFlashResponse, GetMetarsResponse, ListArchivedChoresResponse, ListChoresParams,
+    ListChoresResponse, UpdateFlashParams, UpdateFlashResponse,
+};
+use chores::weather_source::{MetarStation, WeatherSourceConfig};
+
+use crate::alerts::{AlertKind, AlertRule, Alerts};
+use crate::assets::Assets;
+use crate::catch_up::{CatchUpPolicy, CatchUpReport, CaughtUpChore};
+use crate::dashboard_image::{DashboardImageCache, DashboardImageConfig};
+use crate::error::ApiError;
+use crate::events::EventBus;
+use crate::html::RedirectParams;
+use crate::schedule::ChoreSchedule;
 
 #[derive(Deserialize, Debug)]
 struct Chore {
+    /// Label shown on the dashboard, as the chore's key is what its history is stored under
+    #[serde(default)]
+    display_name: Option<String>,
+    /// Keys the chore was previously known by, whose history is moved to its current key
+    #[serde(default)]
+    aliases: Vec<String>,
     description: String,
-    frequency: String,
+    frequency: ChoreSchedule,
+    #[serde(default)]
+    catch_up: CatchUpPolicy,
+}
+
+/// A flash posted each time its frequency fires
+#[derive(Deserialize, Debug)]
+struct RecurringFlash {
+    contents: String,
+    frequency: ChoreSchedule,
+    #[serde(default)]
+    priority: FlashPriority,
+    /// How long each flash is shown before it's acknowledged automatically
+    #[serde(default, with = "humantime_serde")]
+    auto_acknowledge: Option<StdDuration>,
+}
+
+impl Chore {
+    fn display_name<'a>(&'a self, key: &'a str) -> &'a str {
+        self.display_name.as_deref().unwrap_or(key)
+    }
 }
 
 const fn one_day() -> StdDuration {
@@ -42,22 +103,68 @@ const fn one_hour() -> StdDuration {
     StdDuration::from_secs(3600)
 }
 
+const fn ten_minutes() -> StdDuration {
+    StdDuration::from_secs(600)
+}
+
+const fn two_hours() -> StdDuration {
+    StdDuration::from_secs(7200)
+}
+
 const fn default_port() -> u16 {
     4040
 }
 
+/// The host's zone, so schedules keep the times they had before `timezone` could be configured
+fn host_timezone() -> Tz {
+    let name = match iana_time_zone::get_timezone() {
+        Ok(name) => name,
+        Err(e) => {
+            tracing::warn!("Couldn't find the host's time zone, so using UTC: {}", e);
+            return Tz::UTC;
+        }
+    };
+
+    name.parse().unwrap_or_else(|e| {
+        tracing::warn!("Unknown host time zone {:?}, so using UTC: {}", name, e);
+        Tz::UTC
+    })
+}
+
 #[derive(Deserialize, Debug)]
 struct Config {
     #[serde(default = "default_port")]
     port: u16,
     chores: HashMap<String, Chore>,
-    metar_stations: Vec<String>,
+    #[serde(default)]
+    flashes: HashMap<String, RecurringFlash>,
+    /// Household members flashes can be addressed to. Any name is accepted when this is empty
+    #[serde(default)]
+    members: Vec<String>,
+    /// Flashes posted automatically while something is wrong
+    #[serde(default)]
+    alerts: HashMap<AlertKind, AlertRule>,
+    metar_stations: Vec<MetarStation>,
+    /// Where stations without a source of their own are fetched from
+    #[serde(default)]
+    weather_source: WeatherSourceConfig,
+    /// How often the stations are fetched in the background
+    #[serde(with = "humantime_serde", default = "ten_minutes")]
+    metar_poll_interval: StdDuration,
+    /// How old an observation can be before it's marked stale
+    #[serde(with = "humantime_serde", default = "two_hours")]
+    metar_stale_after: StdDuration,
     #[serde(with = "humantime_serde")]
     overdue_time: StdDuration,
     #[serde(with = "humantime_serde", default = "one_day")]
     lookahead_time: StdDuration,
     #[serde(with = "humantime_serde", default = "one_hour")]
     check_interval: StdDuration,
+    /// IANA name of the zone schedules and day boundaries are evaluated in, the host's by default
+    #[serde(default = "host_timezone")]
+    timezone: Tz,
+    #[serde(default)]
+    dashboard_image: DashboardImageConfig,
 }
 
 impl Config {
@@ -66,6 +173,55 @@ impl Config {
 
         Ok(Arc::new(serde_json::from_str(&contents)?))
     }
+
+    fn now(&self) -> DateTime<Tz> {
+        Utc::now().with_timezone(&self.timezone)
+    }
+
+    fn localize_timestamp(&self, timestamp: i64) -> Result<DateTime<Tz>> {
+        match self.timezone.timestamp_opt(timestamp, 0) {
+            LocalResult::Single(time) => Ok(time),
+            _ => Err(anyhow!("Invalid timestamp {}", timestamp)),
+        }
+    }
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug)]
+enum OutputFormat {
+    Table,
+    Json,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Run the scheduler and webserver (the default)
+    Serve {
+        /// Serve the frontend from this checkout's `assets/html` and `dist` instead of the copies
+        /// embedded in the binary, for development
+        #[arg(long)]
+        assets_dir: Option<PathBuf>,
+    },
+    /// Validate the config file and report every problem found
+    Check,
+    /// Print the instances each chore would be scheduled for
+    Preview {
+        /// Number of days to project ahead
+        #[arg(long, default_value_t = 7)]
+        days: u32,
+        #[arg(long, value_enum, default_value_t = OutputFormat::Table)]
+        format: OutputFormat,
+    },
+    /// Talk to a running server over its HTTP API
+    Client(client::ClientArgs),
+    /// Show a live dashboard in the terminal
+    Tui(tui::TuiArgs),
+    /// Print the OpenAPI spec for the HTTP API, as checked in at `openapi.json`
+    Openapi {
+        /// Print the spec's schemas as the TypeScript declarations checked in at
+        /// `assets/ts/api.d.ts` instead
+        #[arg(long)]
+        typescript: bool,
+    },
 }
 
 /// Chores webserver
@@ -73,128 +229,590 @@ impl Config {
 #[command(author, version, about, long_about = None)]
 struct Args {
     /// Config file to load from
-    #[arg(long, default_value = "config.json")]
+    #[arg(long, default_value = "config.json", global = true)]
     config_path: String,
+    #[command(subcommand)]
+    command: Option<Command>,
 }
 
-async fn update_chores(pool: Arc<SqlitePool>, config: Arc<Config>) -> Result<()> {
-    loop {
-        let now = Local::now();
-        let lookahead = now + Duration::from_std(config.lookahead_time)?;
-        let overdue_duration = Duration::from_std(config.overdue_time)?;
+async fn insert_chore(
+    txn: &mut Transaction<'_, Sqlite>,
+    title: &str,
+    expected_completion_time: i64,
+    overdue_timestamp: i64,
+    expiration_timestamp: i64,
+) -> Result<Option<i64>> {
+    let result = sqlx::query!(
+        r#"
+        INSERT OR IGNORE INTO `chores`
+        (
+            `title`,
+            `expected_completion_time`,
+            `overdue_time`,
+            `expiration_time`
+        )
+        VALUES
+        (
+            ?1,
+            ?2,
+            ?3,
+            ?4
+        )
+        "#,
+        title,
+        expected_completion_time,
+        overdue_timestamp,
+        expiration_timestamp,
+    )
+    .execute(&mut *txn)
+    .await?;
 
-        let mut conn = pool.acquire().await?;
-        let mut txn = conn.begin().await?;
+    // nothing is inserted when the instance already exists
+    Ok((result.rows_affected() > 0).then(|| result.last_insert_rowid()))
+}
 
-        let mut added_chores = 0;
+/// Titles of the chores in the config as a JSON array, for matching against in SQL with `json_each`
+fn configured_titles(config: &Config) -> Result<String> {
+    Ok(serde_json::to_string(
+        &config.chores.keys().collect::<Vec<_>>(),
+    )?)
+}
 
-        let row = sqlx::query(
-            r#"
-            SELECT
-                CAST(`update_timestamp` AS INTEGER) AS `update_timestamp`
-            FROM `updates`
-            ORDER BY `update_timestamp` DESC
-            LIMIT 1
-            "#,
+/// Cancels the pending instances of chores that were removed from the config, returning the events
+/// to publish once the transaction commits. Instances that already expired are left to become missed.
+async fn cancel_orphaned_chores(
+    txn: &mut Transaction<'_, Sqlite>,
+    config: &Config,
+    now_timestamp: i64,
+) -> Result<Vec<ApiEvent>> {
+    let titles = configured_titles(config)?;
+    let rows = sqlx::query!(
+        r#"
+        SELECT
+            `id`,
+            `title`,
+            `expected_completion_time`
+        FROM `chores`
+        WHERE
+            `status` = 'assigned'
+            AND `expiration_time` >= ?1
+            AND `title` NOT IN (SELECT `value` FROM json_each(?2))
+        "#,
+        now_timestamp,
+        titles,
+    )
+    .fetch_all(&mut *txn)
+    .await?;
+
+    let mut events = Vec::new();
+    for row in rows {
+        sqlx::query!(
+            "UPDATE `chores` SET `status` = 'cancelled' WHERE `id` = ?1",
+            row.id,
         )
-        .fetch_optional(&mut txn)
+        .execute(&mut *txn)
         .await?;
 
-        let last_update = match row {
-            Some(row) => row
-                .try_get("update_timestamp")
-                .ok()
-                .unwrap_or(now.timestamp()),
-            None => now.timestamp(),
-        };
-        let last_update_date = Local.timestamp(last_update, 0);
+        events.push(ApiEvent::ChoreStatusChanged {
+            id: row.id,
+            title: row.title,
+            expected_completion_time: row.expected_completion_time,
+            status: ApiStatus::Cancelled,
+        });
+    }
+
+    if !events.is_empty() {
+        tracing::info!("Cancelled {} instance(s) of removed chores", events.len());
+    }
+
+    Ok(events)
+}
+
+/// Reassigns the cancelled instances of chores that were added back to the config, for as long as
+/// they're still due, returning the events to publish once the transaction commits
+async fn restore_readded_chores(
+    txn: &mut Transaction<'_, Sqlite>,
+    config: &Config,
+    now_timestamp: i64,
+) -> Result<Vec<ApiEvent>> {
+    let titles = configured_titles(config)?;
+    let rows = sqlx::query!(
+        r#"
+        SELECT
+            `id`,
+            `title`,
+            `expected_completion_time`
+        FROM `chores`
+        WHERE
+            `status` = 'cancelled'
+            AND `expiration_time` >= ?1
+            AND `title` IN (SELECT `value` FROM json_each(?2))
+        "#,
+        now_timestamp,
+        titles,
+    )
+    .fetch_all(&mut *txn)
+    .await?;
 
+    let mut events = Vec::new();
+    for row in rows {
         sqlx::query!(
-            r#"
-            UPDATE `chores`
-            SET `status` = 'missed'
-            WHERE
-                CAST(`expiration_time` AS INTEGER) < STRFTIME('%s', 'now')
-                AND `status` = 'assigned'
-            "#,
+            "UPDATE `chores` SET `status` = 'assigned' WHERE `id` = ?1",
+            row.id,
         )
-        .execute(&mut txn)
+        .execute(&mut *txn)
         .await?;
 
-        for (title, chore) in config.chores.iter() {
-            let chore_title = title.to_string();
-
-            let schedule: Schedule = chore.frequency.parse()?;
-
-            let mut expected_completion_time: Option<i64> = None;
-
-            for next_time in schedule.after(&last_update_date) {
-                let next_timestamp = next_time.timestamp();
-                if let Some(time) = expected_completion_time {
-                    let overdue_timestamp = time + overdue_duration.num_seconds();
-
-                    sqlx::query!(
-                        r#"
-                        INSERT OR IGNORE INTO `chores`
-                        (
-                            `title`,
-                            `expected_completion_time`,
-                            `overdue_time`,
-                            `expiration_time`
-                        )
-                        VALUES
-                        (
-                            ?1,
-                            ?2,
-                            ?3,
-                            ?4
-                        )
-                        "#,
-                        chore_title,
-                        time,
-                        overdue_timestamp,
-                        next_timestamp,
-                    )
-                    .execute(&mut txn)
-                    .await?;
-                }
+        events.push(ApiEvent::ChoreStatusChanged {
+            id: row.id,
+            title: row.title,
+            expected_completion_time: row.expected_completion_time,
+            status: ApiStatus::Assigned,
+        });
+    }
 
-                expected_completion_time = Some(next_timestamp);
+    if !events.is_empty() {
+        tracing::info!("Restored {} instance(s) of re-added chores", events.len());
+    }
 
-                if next_time > lookahead {
-                    break;
-                }
+    Ok(events)
+}
 
-                added_chores += 1;
-            }
+/// Posts a recurring flash for each time it fires before `lookahead`, shown from that time on.
+/// Of the times missed since the last update, only the latest is posted, so a server that was down
+/// doesn't post a backlog of stale reminders at once. Each time is only ever posted once, even if
+/// its flash is deleted before it's shown
+async fn post_recurring_flashes(
+    txn: &mut Transaction<'_, Sqlite>,
+    name: &str,
+    recurring: &RecurringFlash,
+    last_update: &DateTime<Tz>,
+    now: &DateTime<Tz>,
+    lookahead: &DateTime<Tz>,
+) -> Result<Vec<Flash>> {
+    let last_posted = sqlx::query_scalar!(
+        "SELECT `last_posted_time` FROM `recurring_flashes` WHERE `key` = ?1",
+        name,
+    )
+    .fetch_optional(&mut *txn)
+    .await?;
+
+    let times = recurring.frequency.occurrences(last_update, lookahead);
+    let (fired, upcoming): (Vec<_>, Vec<_>) = times
+        .into_iter()
+        .filter(|time| time <= lookahead)
+        .filter(|time| last_posted.is_none_or(|last_posted| time.timestamp() > last_posted))
+        .partition(|time| time <= now);
+
+    let now_timestamp = now.timestamp();
+    let mut flashes = Vec::new();
+    for time in fired.last().into_iter().chain(upcoming.iter()) {
+        let fired_at = time.timestamp();
+        let expires_at = recurring
+            .auto_acknowledge
+            .map(|duration| fired_at + duration.as_secs() as i64);
+        if expires_at.is_some_and(|expires_at| expires_at <= now_timestamp) {
+            continue;
+        }
+
+        let priority = recurring.priority.as_str();
+        let result = sqlx::query!(
+            r#"
+            INSERT OR IGNORE INTO `flashes`
+            (
+                `contents`,
+                `created_at`,
+                `priority`,
+                `show_from`,
+                `expires_at`,
+                `recurring_key`,
+                `recurring_time`
+            )
+            VALUES
+            (
+                ?1,
+                ?2,
+                ?3,
+                ?2,
+                ?4,
+                ?5,
+                ?2
+            )
+            "#,
+            recurring.contents,
+            fired_at,
+            priority,
+            expires_at,
+            name,
+        )
+        .execute(&mut *txn)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            continue;
         }
 
+        flashes.push(Flash {
+            id: result.last_insert_rowid(),
+            contents: recurring.contents.clone(),
+            contents_html: markdown::render(&recurring.contents),
+            created_at: fired_at,
+            priority: recurring.priority,
+            show_from: Some(fired_at),
+            expires_at,
+            author: None,
+            recipient: None,
+            updated_at: None,
+        });
+    }
+
+    if let Some(latest) = upcoming.last().or(fired.last()) {
+        let latest = latest.timestamp();
         sqlx::query!(
             r#"
-            INSERT OR IGNORE INTO `updates`
+            INSERT INTO `recurring_flashes`
             (
-                `update_timestamp`
+                `key`,
+                `last_posted_time`
             )
             VALUES
             (
-                ?1
+                ?1,
+                ?2
             )
+            ON CONFLICT (`key`) DO UPDATE SET
+                `last_posted_time` = excluded.`last_posted_time`
             "#,
-            last_update,
+            name,
+            latest,
         )
-        .execute(&mut txn)
+        .execute(&mut *txn)
         .await?;
+    }
 
-        txn.commit().await?;
+    Ok(flashes)
+}
 
-        tracing::debug!("Added {} chore(s)", added_chores);
+/// Retires flashes whose display window has ended, as if they had been dismissed when they expired,
+/// returning the events to publish once the transaction commits
+async fn expire_flashes(
+    txn: &mut Transaction<'_, Sqlite>,
+    now_timestamp: i64,
+) -> Result<Vec<ApiEvent>> {
+    let ids: Vec<i64> = sqlx::query_scalar(
+        "SELECT `id` FROM `flashes` WHERE `acknowledged` = 0 AND `expires_at` <= ?1",
+    )
+    .bind(now_timestamp)
+    .fetch_all(&mut *txn)
+    .await?;
 
-        tokio::time::sleep(config.check_interval).await
+    sqlx::query!(
+        r#"
+        UPDATE `flashes`
+        SET
+            `acknowledged` = 1,
+            `acknowledged_at` = `expires_at`
+        WHERE
+            `acknowledged` = 0
+            AND `expires_at` <= ?1
+        "#,
+        now_timestamp,
+    )
+    .execute(&mut *txn)
+    .await?;
+
+    if !ids.is_empty() {
+        tracing::debug!("Expired {} flash(es)", ids.len());
+    }
+
+    Ok(ids
+        .into_iter()
+        .map(|id| ApiEvent::FlashDismissed { id })
+        .collect())
+}
+
+/// Moves the instances of renamed chores from their aliases over to their current keys
+async fn apply_chore_aliases(pool: &SqlitePool, config: &Config) -> Result<()> {
+    let mut txn = pool.begin().await?;
+
+    for (title, chore) in config.chores.iter() {
+        for alias in chore.aliases.iter() {
+            // instances the chore already has under its current key are left as they are
+            let renamed = sqlx::query!(
+                "UPDATE OR IGNORE `chores` SET `title` = ?1 WHERE `title` = ?2",
+                title,
+                alias,
+            )
+            .execute(&mut txn)
+            .await?
+            .rows_affected();
+
+            if renamed > 0 {
+                tracing::info!(
+                    "Renamed {} instance(s) of \"{}\" to \"{}\"",
+                    renamed,
+                    alias,
+                    title
+                );
+            }
+
+            let remaining: i64 = sqlx::query("SELECT COUNT(*) FROM `chores` WHERE `title` = ?1")
+                .bind(alias)
+                .fetch_one(&mut txn)
+                .await?
+                .try_get(0)?;
+
+            if remaining > 0 {
+                tracing::warn!(
+                    "{} instance(s) of \"{}\" were not renamed, as \"{}\" already has instances \
+                     due at the same times",
+                    remaining,
+                    alias,
+                    title
+                );
+            }
+        }
+    }
+
+    txn.commit().await?;
+
+    Ok(())
+}
+
+/// Runs one pass of the scheduler, reporting on what was caught up with if it's the first since
+/// startup
+async fn update_chores_once(
+    pool: &Arc<SqlitePool>,
+    config: &Config,
+    catch_up_report: &RwLock<Option<CatchUpReport>>,
+    events: &EventBus,
+    first_run: bool,
+) -> Result<()> {
+    let now = config.now();
+    let lookahead = now + Duration::from_std(config.lookahead_time)?;
+    let overdue_duration = Duration::from_std(config.overdue_time)?;
+
+    let now_timestamp = now.timestamp();
+
+    let mut conn = pool.acquire().await?;
+    let mut txn = conn.begin().await?;
+
+    let mut added_chores = 0;
+    // published once the transaction commits
+    let mut pending_events = Vec::new();
+
+    let row = sqlx::query(
+        r#"
+        SELECT
+            `update_timestamp`
+        FROM `updates`
+        ORDER BY `update_timestamp` DESC
+        LIMIT 1
+        "#,
+    )
+    .fetch_optional(&mut txn)
+    .await?;
+
+    let last_update = match row {
+        Some(row) => row
+            .try_get("update_timestamp")
+            .ok()
+            .unwrap_or(now.timestamp()),
+        None => now.timestamp(),
+    };
+    let last_update_date = config.localize_timestamp(last_update)?;
+
+    let mut caught_up_chores = Vec::new();
+
+    for (title, chore) in config.chores.iter() {
+        let times = chore.frequency.occurrences(&last_update_date, &lookahead);
+
+        // Pairs of (expected completion time, expiration time)
+        let occurrences: Vec<(i64, i64)> = times
+            .windows(2)
+            .map(|pair| (pair[0].timestamp(), pair[1].timestamp()))
+            .collect();
+
+        // Occurrences that expired before now elapsed entirely while we weren't running. Only the
+        // first pass since startup can have any; later ones are recorded and marked missed as usual
+        let (missed, current): (Vec<_>, Vec<_>) = occurrences
+            .into_iter()
+            .partition(|(_, expiration)| first_run && *expiration < now.timestamp());
+
+        let backfilled = match chore.catch_up {
+            CatchUpPolicy::Backfill => missed.clone(),
+            CatchUpPolicy::Collapse => missed.last().into_iter().cloned().collect(),
+            CatchUpPolicy::Skip => Vec::new(),
+        };
+
+        if !missed.is_empty() {
+            caught_up_chores.push(CaughtUpChore {
+                title: title.to_string(),
+                policy: chore.catch_up,
+                missed_occurrences: missed.len(),
+                backfilled: backfilled.len(),
+            });
+        }
+
+        for (time, expiration) in backfilled.into_iter().chain(current) {
+            let overdue_timestamp = time + overdue_duration.num_seconds();
+            let inserted =
+                insert_chore(&mut txn, title, time, overdue_timestamp, expiration).await?;
+
+            if let Some(id) = inserted {
+                pending_events.push(ApiEvent::ChoreCreated {
+                    id,
+                    title: title.to_string(),
+                    expected_completion_time: time,
+                });
+                added_chores += 1;
+            }
+        }
+    }
+
+    for (name, recurring) in config.flashes.iter() {
+        let posted = post_recurring_flashes(
+            &mut txn,
+            name,
+            recurring,
+            &last_update_date,
+            &now,
+            &lookahead,
+        )
+        .await?;
+        pending_events.extend(
+            posted
+                .into_iter()
+                .map(|flash| ApiEvent::FlashAdded { flash }),
+        );
+    }
+
+    pending_events.extend(cancel_orphaned_chores(&mut txn, config, now_timestamp).await?);
+    pending_events.extend(restore_readded_chores(&mut txn, config, now_timestamp).await?);
+    pending_events.extend(expire_flashes(&mut txn, now_timestamp).await?);
+
+    let newly_missed = sqlx::query(
+        r#"
+        SELECT
+            `id`,
+            `title`,
+            `expected_completion_time`
+        FROM `chores`
+        WHERE
+            `status` = 'assigned'
+            AND `expiration_time` < ?1
+        "#,
+    )
+    .bind(now_timestamp)
+    .fetch_all(&mut txn)
+    .await?;
+
+    for row in newly_missed {
+        match (
+            row.try_get("id"),
+            row.try_get("title"),
+            row.try_get("expected_completion_time"),
+        ) {
+            (Ok(id), Ok(title), Ok(expected_completion_time)) => {
+                pending_events.push(ApiEvent::ChoreStatusChanged {
+                    id,
+                    title,
+                    expected_completion_time,
+                    status: ApiStatus::Missed,
+                })
+            }
+            _ => tracing::warn!("Missed chore has no ID, title or expected completion time"),
+        }
+    }
+
+    sqlx::query!(
+        r#"
+        UPDATE `chores`
+        SET `status` = 'missed'
+        WHERE
+            `status` = 'assigned'
+            AND `expiration_time` < ?1
+        "#,
+        now_timestamp,
+    )
+    .execute(&mut txn)
+    .await?;
+
+    sqlx::query!(
+        r#"
+        INSERT OR IGNORE INTO `updates`
+        (
+            `update_timestamp`
+        )
+        VALUES
+        (
+            ?1
+        )
+        "#,
+        now_timestamp,
+    )
+    .execute(&mut txn)
+    .await?;
+
+    txn.commit().await?;
+    // returned to the pool, as posting the report below needs one of its own
+    drop(conn);
+
+    tracing::debug!("Added {} chore(s)", added_chores);
+
+    for event in pending_events {
+        events.publish(event);
+    }
+
+    if first_run {
+        let report = CatchUpReport {
+            last_update,
+            caught_up_at: now_timestamp,
+            chores: caught_up_chores,
+        };
+
+        if let Some(contents) = report.flash_contents(MAX_FLASH_LENGTH) {
+            tracing::info!("{}", contents);
+            let params = AddFlashParams {
+                contents,
+                ..Default::default()
+            };
+            // the chores were caught up with all the same, so this doesn't fail the pass
+            if let Err(e) = add_flash_impl(params, pool.clone(), config, events).await {
+                tracing::warn!("Failed to post the catch-up report: {}", e);
+            }
+        }
+
+        *catch_up_report.write().await = Some(report);
     }
+
+    Ok(())
 }
 
-async fn handle_error(_err: std::io::Error) -> impl IntoResponse {
-    (StatusCode::INTERNAL_SERVER_ERROR, "Something went wrong...")
+async fn update_chores(
+    pool: Arc<SqlitePool>,
+    config: Arc<Config>,
+    catch_up_report: Arc<RwLock<Option<CatchUpReport>>>,
+    events: Arc<EventBus>,
+    alerts: Arc<Alerts>,
+) -> Result<()> {
+    let mut first_run = true;
+
+    loop {
+        // a failed pass is retried on the next one rather than taking the server down with it
+        match update_chores_once(&pool, &config, &catch_up_report, &events, first_run).await {
+            Ok(()) => {
+                first_run = false;
+                alerts.scheduler_succeeded().await;
+            }
+            Err(e) => {
+                tracing::error!("Failed to update chores: {}", e);
+                alerts.scheduler_failed(&e).await;
+            }
+        }
+
+        tokio::time::sleep(config.check_interval).await
+    }
 }
 
 #[derive(Serialize, Debug, Clone)]
@@ -203,6 +821,7 @@ enum Status {
     Assigned,
     Completed,
     Missed,
+    Cancelled,
 }
 
 impl FromStr for Status {
@@ -213,77 +832,56 @@ impl FromStr for Status {
             "assigned" => Ok(Status::Assigned),
             "completed" => Ok(Status::Completed),
             "missed" => Ok(Status::Missed),
+            "cancelled" => Ok(Status::Cancelled),
             _ => Err(anyhow!("Unknown status \"{}\"", value)),
         }
     }
 }
 
-#[derive(Serialize, Debug, Clone)]
-#[serde(rename_all(serialize = "lowercase", deserialize = "lowercase"))]
-enum ApiStatus {
-    Upcoming,
-    Assigned,
-    Overdue,
-    Completed,
-    Missed,
-}
-
-#[derive(Serialize, Debug, Clone)]
-struct ApiChore {
-    title: String,
-    description: String,
-    expected_completion_time: i32,
-    status: ApiStatus,
-}
-
-#[derive(Serialize, Debug, Clone)]
-struct ListChoresResponse {
-    success: bool,
-    error: Option<String>,
-    chores: Vec<ApiChore>,
-}
-
-#[derive(Debug, Deserialize)]
-struct ListChoresParams {
-    lookback_days: Option<u32>,
-}
-
 async fn list_chores_impl(
     params: ListChoresParams,
     pool: Arc<SqlitePool>,
     config: Arc<Config>,
 ) -> Result<Vec<ApiChore>> {
     let lookback_days = params.lookback_days.unwrap_or(1);
-    let lookback_timestamp = (Local::now() - Duration::days(lookback_days as i64)).timestamp();
-
-    let now_date = Local::now().date();
-    let next_day = Local.ymd(now_date.year(), now_date.month(), now_date.day()) + Duration::days(1);
-    let next_day = next_day.and_hms(0, 0, 0);
+    let now = config.now();
+    let lookback_timestamp = (now - Duration::days(lookback_days as i64)).timestamp();
+
+    // Midnight can be skipped or repeated by a DST transition, so take the earliest valid instant
+    let next_day = (now.date_naive() + Duration::days(1))
+        .and_hms_opt(0, 0, 0)
+        .ok_or_else(|| anyhow!("Invalid start of day"))?;
+    let next_day = match config.timezone.from_local_datetime(&next_day).earliest() {
+        Some(next_day) => next_day,
+        None => now + Duration::days(1),
+    };
 
     let rows = sqlx::query(
         r#"
         SELECT
+            `id`,
             `title`,
-            CAST(`expected_completion_time` AS INTEGER) AS `expected_completion_time`,
-            STRFTIME('%s', 'now') < CAST(`expected_completion_time` AS INTEGER) AS `upcoming`,
-            STRFTIME('%s', 'now') > CAST(`overdue_time` AS INTEGER) AS `overdue`,
+            `expected_completion_time`,
+            ?3 < `expected_completion_time` AS `upcoming`,
+            ?3 > `overdue_time` AS `overdue`,
             `status`
         FROM `chores`
         WHERE
-            CAST(`expected_completion_time` AS INTEGER) >= ?1
-            AND CAST(`expected_completion_time` AS INTEGER) < ?2
-            AND `status` != 'completed'
+            `expected_completion_time` >= ?1
+            AND `expected_completion_time` < ?2
+            AND `status` NOT IN ('completed', 'cancelled')
         ORDER BY `expected_completion_time` ASC
         "#,
     )
     .bind(lookback_timestamp)
     .bind(next_day.timestamp())
+    .bind(now.timestamp())
     .fetch_all(&*pool)
     .await?;
 
     let mut return_chores = Vec::new();
     for row in rows {
-        let title = match row.try_get("title") {
+        let title: String = match row.try_get("title") {
             Ok(title) => title,
             Err(_) => {
                 tracing::warn!("Chore missing title");
@@ -291,14 +889,24 @@ async fn list_chores_impl(
             }
         };
 
-        let description = match config.chores.get(&title) {
-            Some(c) => c.description.clone(),
-            None => {
-                tracing::warn!("Chore \"{}\" not found in config", title);
+        let id = match row.try_get("id") {
+            Ok(id) => id,
+            Err(_) => {
+                tracing::warn!("No ID found for chore \"{}\"", title);
                 continue;
             }
         };
 
+        let (display_name, description, schedule) = match config.chores.get(&title) {
+            Some(c) => (
+                c.display_name(&title).to_string(),
+                c.description.clone(),
+                c.frequency.describe().to_string(),
+            ),
+            // removed from the config, so only listed with the archived chores
+            None => continue,
+        };
+
         let expected_completion_time = match row.try_get("expected_completion_time") {
             Ok(time) => time,
             Err(_) => {
@@ -307,6 +915,14 @@ async fn list_chores_impl(
             }
         };
 
+        let expected_completion_at = match config.localize_timestamp(expected_completion_time) {
+            Ok(time) => time.to_rfc3339_opts(SecondsFormat::Secs, false),
+            Err(_) => {
+                tracing::warn!("Invalid expected completion time for chore \"{}\"", title);
+                continue;
+            }
+        };
+
         let upcoming = match row.try_get::<i32, &str>("upcoming") {
             Ok(upcoming) => upcoming == 1,
             Err(_) => {
@@ -350,12 +966,18 @@ async fn list_chores_impl(
             }
             (Status::Completed, _, _) => ApiStatus::Completed,
             (Status::Missed, _, _) => ApiStatus::Missed,
+            (Status::Cancelled, _, _) => ApiStatus::Cancelled,
         };
 
         return_chores.push(ApiChore {
-            title: title,
+            id,
+            title,
+            display_name,
+            description_html: markdown::render(&description),
             description,
             expected_completion_time,
+            expected_completion_at,
+            schedule,
             status,
         });
     }
@@ -363,6 +985,14 @@ async fn list_chores_impl(
     Ok(return_chores)
 }
 
+/// List chore instances from the lookback window onwards
+#[utoipa::path(
+    get,
+    path = "/api/chores",
+    tag = "v1",
+    params(ListChoresParams),
+    responses((status = 200, body = ListChoresResponse))
+)]
 async fn list_chores(
     Query(params): Query<ListChoresParams>,
     Extension(pool): Extension<Arc<SqlitePool>>,
@@ -377,86 +1007,296 @@ async fn list_chores(
         Err(e) => Json(ListChoresResponse {
             success: false,
             chores: Vec::new(),
-            error: Some(format!("failed to fetch chores: {}", e)),
+            error: Some(format!("failed to fetch chores: {}", ApiError::from(e))),
         }),
     }
 }
 
-#[derive(Deserialize, Debug)]
-struct CompleteChoreParams {
-    title: String,
-    expected_completion_time: i32,
-}
-
-#[derive(Serialize, Debug)]
-struct CompleteChoreResponse {
-    success: bool,
-    error: Option<String>,
-}
-
-async fn complete_chore_impl(params: CompleteChoreParams, pool: Arc<SqlitePool>) -> Result<()> {
-    sqlx::query!(
+async fn list_archived_chores_impl(
+    pool: Arc<SqlitePool>,
+    config: Arc<Config>,
+) -> Result<Vec<ArchivedChore>> {
+    let titles = configured_titles(&config)?;
+    let rows = sqlx::query!(
         r#"
-        UPDATE `chores`
-        SET
-            `status` = 'completed'
-        WHERE
-            `title` = ?1
-            AND `expected_completion_time` = ?2
+        SELECT
+            `id`,
+            `title`,
+            `expected_completion_time`,
+            `status`
+        FROM `chores`
+        WHERE `title` NOT IN (SELECT `value` FROM json_each(?1))
+        ORDER BY `title` ASC, `expected_completion_time` ASC
         "#,
-        params.title,
-        params.expected_completion_time,
+        titles,
     )
-    .execute(&*pool)
+    .fetch_all(&*pool)
     .await?;
 
-    Ok(())
+    let mut archived: Vec<ArchivedChore> = Vec::new();
+    for row in rows {
+        let status = match row.status.parse::<Status>() {
+            Ok(Status::Assigned) => ApiStatus::Assigned,
+            Ok(Status::Completed) => ApiStatus::Completed,
+            Ok(Status::Missed) => ApiStatus::Missed,
+            Ok(Status::Cancelled) => ApiStatus::Cancelled,
+            Err(_) => {
+                tracing::warn!(
+                    "Unknown status \"{}\" for chore \"{}\"",
+                    row.status,
+                    row.title
+                );
+                continue;
+            }
+        };
+
+        let instance = ArchivedChoreInstance {
+            id: row.id,
+            expected_completion_time: row.expected_completion_time,
+            status,
+        };
+
+        match archived.last_mut() {
+            Some(chore) if chore.title == row.title => chore.instances.push(instance),
+            _ => archived.push(ArchivedChore {
+                title: row.title,
+                instances: vec![instance],
+            }),
+        }
+    }
+
+    Ok(archived)
 }
 
-async fn complete_chore(
-    Form(params): Form<CompleteChoreParams>,
+/// List chores that were removed from the config, along with their history
+#[utoipa::path(
+    get,
+    path = "/api/chores/archived",
+    tag = "v1",
+    responses((status = 200, body = ListArchivedChoresResponse))
+)]
+async fn list_archived_chores(
     Extension(pool): Extension<Arc<SqlitePool>>,
-    Extension(_config): Extension<Arc<Config>>,
-) -> Json<CompleteChoreResponse> {
-    match complete_chore_impl(params, pool).await {
-        Ok(()) => Json(CompleteChoreResponse {
+    Extension(config): Extension<Arc<Config>>,
+) -> Json<ListArchivedChoresResponse> {
+    match list_archived_chores_impl(pool, config).await {
+        Ok(chores) => Json(ListArchivedChoresResponse {
             success: true,
+            chores,
             error: None,
         }),
-        Err(e) => Json(CompleteChoreResponse {
+        Err(e) => Json(ListArchivedChoresResponse {
             success: false,
-            error: Some(format!("failed to mark chore as completed: {}", e)),
+            chores: Vec::new(),
+            error: Some(format!(
+                "failed to fetch archived chores: {}",
+                ApiError::from(e)
+            )),
         }),
     }
 }
 
-async fn index() -> impl IntoResponse {
-    let mime_type = mime_guess::from_path(INDEX_PATH).first_or_text_plain();
-
-    match read_to_string(INDEX_PATH) {
-        Ok(contents) => Response::builder()
-            .status(StatusCode::OK)
-            .header(
-                header::CONTENT_TYPE,
-                HeaderValue::from_str(mime_type.as_ref()).unwrap(),
+async fn complete_chore_impl(
+    params: CompleteChoreParams,
+    pool: Arc<SqlitePool>,
+    events: &EventBus,
+) -> Result<ChoreInstance> {
+    let (instance, description) = match (params.id, params.title, params.expected_completion_time) {
+        (Some(id), _, _) => (
+            sqlx::query_as!(
+                ChoreInstance,
+                r#"
+                SELECT
+                    `id`,
+                    `title`,
+                    `expected_completion_time`
+                FROM `chores`
+                WHERE `id` = ?1
+                "#,
+                id,
             )
-            .body(body::boxed(Full::from(contents)))
-            .unwrap(),
-        Err(e) => Response::builder()
-            .status(StatusCode::INTERNAL_SERVER_ERROR)
-            .body(body::boxed(Full::from(format!(
-                "Error fetching path: {}",
-                e
-            ))))
-            .unwrap(),
+            .fetch_optional(&*pool)
+            .await?,
+            format!("chore {}", id),
+        ),
+        (None, Some(title), Some(expected_completion_time)) => (
+            sqlx::query_as!(
+                ChoreInstance,
+                r#"
+                SELECT
+                    `id`,
+                    `title`,
+                    `expected_completion_time`
+                FROM `chores`
+                WHERE
+                    `title` = ?1
+                    AND `expected_completion_time` = ?2
+                "#,
+                title,
+                expected_completion_time,
+            )
+            .fetch_optional(&*pool)
+            .await?,
+            format!("chore \"{}\" due at {}", title, expected_completion_time),
+        ),
+        _ => {
+            return Err(ApiError::bad_request(
+                "either id or both title and expected_completion_time are required",
+            )
+            .into())
+        }
+    };
+
+    let instance = instance.ok_or_else(|| ApiError::not_found(format!("no {}", description)))?;
+
+    let result = sqlx::query!(
+        r#"
+        UPDATE `chores`
+        SET
+            `status` = 'completed'
+        WHERE
+            `id` = ?1
+            AND `status` IN ('assigned', 'missed')
+        "#,
+        instance.id,
+    )
+    .execute(&*pool)
+    .await?;
+
+    if result.rows_affected() == 0 {
+        // read back afterwards so a concurrent completion or cancellation is reported as it stands
+        let status =
+            sqlx::query_scalar!("SELECT `status` FROM `chores` WHERE `id` = ?1", instance.id,)
+                .fetch_one(&*pool)
+                .await?;
+        let message = match status.as_str() {
+            "cancelled" => format!("{} was cancelled", description),
+            _ => format!("{} is already completed", description),
+        };
+        return Err(ApiError::conflict(message).into());
     }
+
+    events.publish(ApiEvent::ChoreCompleted {
+        id: instance.id,
+        title: instance.title.clone(),
+        expected_completion_time: instance.expected_completion_time,
+    });
+
+    Ok(instance)
 }
 
-#[derive(Debug, Serialize)]
-struct Flash {
-    id: i64,
-    contents: String,
-    created_at: i32,
+/// Mark a chore instance as completed
+#[utoipa::path(
+    post,
+    path = "/api/chores/complete",
+    tag = "v1",
+    params(RedirectParams),
+    request_body(content = CompleteChoreParams, content_type = "application/x-www-form-urlencoded"),
+    responses(
+        (status = 200, body = CompleteChoreResponse),
+        (status = 303, description = "Redirected to `redirect`, with any error in its `error` parameter")
+    )
+)]
+async fn complete_chore(
+    Query(redirect): Query<RedirectParams>,
+    Form(params): Form<CompleteChoreParams>,
+    Extension(pool): Extension<Arc<SqlitePool>>,
+    Extension(_config): Extension<Arc<Config>>,
+    Extension(events): Extension<Arc<EventBus>>,
+) -> Response {
+    if let Some(rejection) = redirect.reject_invalid() {
+        return rejection;
+    }
+
+    let result = complete_chore_impl(params, pool, &events)
+        .await
+        .map(|_| ())
+        .map_err(|e| format!("failed to mark chore as completed: {}", ApiError::from(e)));
+
+    if let Some(response) = redirect.respond(result.clone().err()) {
+        return response;
+    }
+
+    match result {
+        Ok(()) => Json(CompleteChoreResponse {
+            success: true,
+            error: None,
+        }),
+        Err(error) => Json(CompleteChoreResponse {
+            success: false,
+            error: Some(error),
+        }),
+    }
+    .into_response()
+}
+
+/// Reads the columns of a flash shared by every query, logging and skipping rows that are malformed
+fn flash_from_row(row: &SqliteRow) -> Option<Flash> {
+    let id = match row.try_get("id") {
+        Ok(id) => id,
+        Err(_) => {
+            tracing::warn!("Flash missing ID");
+            return None;
+        }
+    };
+    let contents = match row.try_get::<String, &str>("contents") {
+        Ok(contents) => contents,
+        Err(_) => {
+            tracing::warn!("Flash missing contents");
+            return None;
+        }
+    };
+    let created_at = match row.try_get("created_at") {
+        Ok(created_at) => created_at,
+        Err(_) => {
+            tracing::warn!("Flash missing creation timestamp");
+            return None;
+        }
+    };
+    let priority = match row.try_get::<&str, &str>("priority") {
+        Ok(priority) => match priority.parse() {
+            Ok(priority) => priority,
+            Err(e) => {
+                tracing::warn!("{} for flash {}", e, id);
+                return None;
+            }
+        },
+        Err(_) => {
+            tracing::warn!("Flash {} missing priority", id);
+            return None;
+        }
+    };
+    let (show_from, expires_at) = match (row.try_get("show_from"), row.try_get("expires_at")) {
+        (Ok(show_from), Ok(expires_at)) => (show_from, expires_at),
+        _ => {
+            tracing::warn!("Flash {} has an invalid display window", id);
+            return None;
+        }
+    };
+    let (author, recipient, updated_at) = match (
+        row.try_get("author"),
+        row.try_get("recipient"),
+        row.try_get("updated_at"),
+    ) {
+        (Ok(author), Ok(recipient), Ok(updated_at)) => (author, recipient, updated_at),
+        _ => {
+            tracing::warn!("Flash {} has an invalid author, recipient or edit time", id);
+            return None;
+        }
+    };
+
+    Some(Flash {
+        id,
+        contents_html: markdown::render(&contents),
+        contents,
+        created_at,
+        priority,
+        show_from,
+        expires_at,
+        author,
+        recipient,
+        updated_at,
+    })
 }
 
 async fn get_flashes_impl(pool: Arc<SqlitePool>) -> Result<Vec<Flash>> {
@@ -465,58 +1305,144 @@ async fn get_flashes_impl(pool: Arc<SqlitePool>) -> Result<Vec<Flash>> {
         SELECT
             `id`,
             `contents`,
-            CAST(`created_at` AS INTEGER) AS `created_at`
+            `created_at`,
+            `priority`,
+            `show_from`,
+            `expires_at`,
+            `author`,
+            `recipient`,
+            `updated_at`
         FROM `flashes`
         WHERE
-            `acknowledged` != 1
-        ORDER BY `created_at` ASC
+            `acknowledged` = 0
+            AND (`show_from` IS NULL OR `show_from` <= ?1)
+            AND (`expires_at` IS NULL OR `expires_at` > ?1)
+        ORDER BY
+            CASE `priority` WHEN 'urgent' THEN 0 WHEN 'warning' THEN 1 ELSE 2 END ASC,
+            `created_at` ASC
+        "#,
+    )
+    .bind(Utc::now().timestamp())
+    .fetch_all(&*pool)
+    .await?;
+
+    Ok(rows.iter().filter_map(flash_from_row).collect())
+}
+
+const DEFAULT_HISTORY_LIMIT: u32 = 50;
+const MAX_HISTORY_LIMIT: u32 = 200;
+
+async fn flash_history_impl(
+    params: FlashHistoryParams,
+    pool: Arc<SqlitePool>,
+) -> Result<FlashHistoryPage> {
+    let limit = params
+        .limit
+        .unwrap_or(DEFAULT_HISTORY_LIMIT)
+        .clamp(1, MAX_HISTORY_LIMIT);
+    let offset = params.offset.unwrap_or(0);
+
+    let total = sqlx::query_scalar("SELECT COUNT(*) FROM `flashes`")
+        .fetch_one(&*pool)
+        .await?;
+
+    let rows = sqlx::query(
+        r#"
+        SELECT
+            `id`,
+            `contents`,
+            `created_at`,
+            `priority`,
+            `show_from`,
+            `expires_at`,
+            `author`,
+            `recipient`,
+            `updated_at`,
+            `acknowledged`,
+            `acknowledged_at`,
+            `acknowledged_by`
+        FROM `flashes`
+        ORDER BY
+            `created_at` DESC,
+            `id` DESC
+        LIMIT ?1
+        OFFSET ?2
         "#,
     )
+    .bind(limit)
+    .bind(offset)
     .fetch_all(&*pool)
     .await?;
 
     let mut flashes = Vec::new();
     for row in rows {
-        let id = match row.try_get("id") {
-            Ok(id) => id,
-            Err(_) => {
-                tracing::warn!("Flash missing ID");
-                continue;
-            }
+        let flash = match flash_from_row(&row) {
+            Some(flash) => flash,
+            None => continue,
         };
-        let contents = match row.try_get("contents") {
-            Ok(contents) => contents,
-            Err(_) => {
-                tracing::warn!("Flash missing contents");
-                continue;
+        let (acknowledged, acknowledged_at, acknowledged_by) = match (
+            row.try_get("acknowledged"),
+            row.try_get("acknowledged_at"),
+            row.try_get("acknowledged_by"),
+        ) {
+            (Ok(acknowledged), Ok(acknowledged_at), Ok(acknowledged_by)) => {
+                (acknowledged, acknowledged_at, acknowledged_by)
             }
-        };
-        let created_at = match row.try_get("created_at") {
-            Ok(created_at) => created_at,
-            Err(_) => {
-                tracing::warn!("Flash missing creation timestamp");
+            _ => {
+                tracing::warn!("Flash {} has an invalid acknowledgement", flash.id);
                 continue;
             }
         };
 
-        flashes.push(Flash {
-            id,
-            contents,
-            created_at,
+        flashes.push(FlashHistoryEntry {
+            flash,
+            acknowledged,
+            acknowledged_at,
+            acknowledged_by,
         });
     }
 
-    Ok(flashes)
+    Ok(FlashHistoryPage { flashes, total })
 }
 
-// TODO: make into flattened enum
-#[derive(Debug, Serialize)]
-struct GetFlashResponse {
-    success: bool,
-    error: Option<String>,
-    flashes: Vec<Flash>,
+/// List every message, including dismissed ones, newest first
+#[utoipa::path(
+    get,
+    path = "/api/flashes/history",
+    tag = "v1",
+    params(FlashHistoryParams),
+    responses((status = 200, body = FlashHistoryResponse))
+)]
+async fn flash_history(
+    Query(params): Query<FlashHistoryParams>,
+    Extension(pool): Extension<Arc<SqlitePool>>,
+) -> Json<FlashHistoryResponse> {
+    match flash_history_impl(params, pool).await {
+        Ok(page) => Json(FlashHistoryResponse {
+            success: true,
+            error: None,
+            flashes: page.flashes,
+            total: page.total,
+        }),
+        Err(e) => Json(FlashHistoryResponse {
+            success: false,
+            error: Some(format!(
+                "failed to fetch flash history: {}",
+                ApiError::from(e)
+            )),
+            flashes: Vec::new(),
+            total: 0,
+        }),
+    }
 }
 
+/// List messages that haven't been dismissed
+#[utoipa::path(
+    get,
+    path = "/api/flashes",
+    tag = "v1",
+    responses((status = 200, body = GetFlashResponse))
+)]
 async fn get_flashes(
     Extension(pool): Extension<Arc<SqlitePool>>,
     Extension(_config): Extension<Arc<Config>>,
@@ -530,134 +1456,537 @@ async fn get_flashes(
         Err(e) => Json(GetFlashResponse {
             success: false,
             flashes: Vec::new(),
-            error: Some(format!("failed to fetch flashes: {}", e)),
+            error: Some(format!("failed to fetch flashes: {}", ApiError::from(e))),
         }),
     }
 }
 
-#[derive(Debug, Deserialize)]
-struct AddFlashParams {
-    contents: String,
+/// Longest flash, in characters, so one message can't crowd everything else off the dashboard
+const MAX_FLASH_LENGTH: usize = 2000;
+const MAX_MEMBER_NAME_LENGTH: usize = 100;
+
+/// Checks a flash's contents and display window, as given when adding or editing it
+fn validate_flash(contents: &str, show_from: Option<i64>, expires_at: Option<i64>) -> Result<()> {
+    if contents.trim().is_empty() {
+        return Err(ApiError::bad_request("message must not be empty").into());
+    }
+    if contents.chars().count() > MAX_FLASH_LENGTH {
+        return Err(ApiError::bad_request(format!(
+            "message must be at most {} characters",
+            MAX_FLASH_LENGTH
+        ))
+        .into());
+    }
+
+    if let Some(expires_at) = expires_at {
+        if expires_at <= Utc::now().timestamp() {
+            return Err(ApiError::bad_request("expires_at is already in the past").into());
+        }
+        if show_from.is_some_and(|show_from| expires_at <= show_from) {
+            return Err(ApiError::bad_request("expires_at must be after show_from").into());
+        }
+    }
+
+    Ok(())
 }
 
-#[derive(Serialize, Debug)]
-struct AddFlashResponse {
-    success: bool,
-    id: Option<i64>,
-    error: Option<String>,
+/// Trims a household member's name, treating a blank one as no one
+fn member_name(name: Option<String>) -> Result<Option<String>> {
+    let name = match name.map(|name| name.trim().to_string()) {
+        Some(name) if !name.is_empty() => name,
+        _ => return Ok(None),
+    };
+
+    if name.chars().count() > MAX_MEMBER_NAME_LENGTH {
+        return Err(ApiError::bad_request(format!(
+            "names must be at most {} characters",
+            MAX_MEMBER_NAME_LENGTH
+        ))
+        .into());
+    }
+
+    Ok(Some(name))
 }
 
-async fn add_flash_impl(params: AddFlashParams, pool: Arc<SqlitePool>) -> Result<i64> {
+/// Resolves a flash's recipient to the spelling used in the config's `members`
+fn resolve_recipient(config: &Config, recipient: Option<String>) -> Result<Option<String>> {
+    let recipient = match member_name(recipient)? {
+        Some(recipient) => recipient,
+        None => return Ok(None),
+    };
+
+    if config.members.is_empty() {
+        return Ok(Some(recipient));
+    }
+
+    match config
+        .members
+        .iter()
+        .find(|member| member.eq_ignore_ascii_case(&recipient))
+    {
+        Some(member) => Ok(Some(member.clone())),
+        None => Err(ApiError::bad_request(format!(
+            "{} is not one of the configured members",
+            recipient
+        ))
+        .into()),
+    }
+}
+
+async fn add_flash_impl(
+    params: AddFlashParams,
+    pool: Arc<SqlitePool>,
+    config: &Config,
+    events: &EventBus,
+) -> Result<i64> {
+    validate_flash(&params.contents, params.show_from, params.expires_at)?;
+
+    let author = member_name(params.author)?;
+    let recipient = resolve_recipient(config, params.recipient)?;
+    let priority = params.priority.as_str();
+
     let id = sqlx::query!(
-        "INSERT INTO `flashes` (`contents`) VALUES (?1)",
+        r#"
+        INSERT INTO `flashes`
+        (
+            `contents`,
+            `priority`,
+            `show_from`,
+            `expires_at`,
+            `author`,
+            `recipient`
+        )
+        VALUES
+        (
+            ?1,
+            ?2,
+            ?3,
+            ?4,
+            ?5,
+            ?6
+        )
+        "#,
         params.contents,
+        priority,
+        params.show_from,
+        params.expires_at,
+        author,
+        recipient,
     )
     .execute(&*pool)
     .await?
     .last_insert_rowid();
 
+    let created_at = sqlx::query("SELECT `created_at` FROM `flashes` WHERE `id` = ?1")
+        .bind(id)
+        .fetch_one(&*pool)
+        .await?
+        .try_get("created_at")?;
+
+    events.publish(ApiEvent::FlashAdded {
+        flash: Flash {
+            id,
+            contents_html: markdown::render(&params.contents),
+            contents: params.contents,
+            created_at,
+            priority: params.priority,
+            show_from: params.show_from,
+            expires_at: params.expires_at,
+            author,
+            recipient,
+            updated_at: None,
+        },
+    });
+
     Ok(id)
 }
 
+/// Add a message to the dashboard
+#[utoipa::path(
+    post,
+    path = "/api/flashes",
+    tag = "v1",
+    params(RedirectParams),
+    request_body(content = AddFlashParams, content_type = "application/x-www-form-urlencoded"),
+    responses(
+        (status = 200, body = AddFlashResponse),
+        (status = 303, description = "Redirected to `redirect`, with any error in its `error` parameter")
+    )
+)]
 async fn add_flash(
+    Query(redirect): Query<RedirectParams>,
     Form(params): Form<AddFlashParams>,
     Extension(pool): Extension<Arc<SqlitePool>>,
-    Extension(_config): Extension<Arc<Config>>,
-) -> Json<AddFlashResponse> {
-    match add_flash_impl(params, pool).await {
+    Extension(config): Extension<Arc<Config>>,
+    Extension(events): Extension<Arc<EventBus>>,
+) -> Response {
+    if let Some(rejection) = redirect.reject_invalid() {
+        return rejection;
+    }
+
+    let result = add_flash_impl(params, pool, &config, &events)
+        .await
+        .map_err(|e| format!("failed to add flash: {}", ApiError::from(e)));
+
+    if let Some(response) = redirect.respond(result.clone().err()) {
+        return response;
+    }
+
+    match result {
         Ok(id) => Json(AddFlashResponse {
             success: true,
             id: Some(id),
             error: None,
         }),
-        Err(e) => Json(AddFlashResponse {
+        Err(error) => Json(AddFlashResponse {
             success: false,
             id: None,
-            error: Some(format!("failed to add flash: {}", e)),
+            error: Some(error),
         }),
     }
+    .into_response()
 }
 
-#[derive(Debug, Deserialize)]
-struct DismissFlashParams {
+/// Replaces a message that hasn't been dismissed yet, keeping its author and creation time
+async fn update_flash_impl(
     id: i64,
+    params: UpdateFlashParams,
+    pool: Arc<SqlitePool>,
+    config: &Config,
+    events: &EventBus,
+) -> Result<Flash> {
+    validate_flash(&params.contents, params.show_from, params.expires_at)?;
+
+    let recipient = resolve_recipient(config, params.recipient)?;
+    let priority = params.priority.as_str();
+    let now_timestamp = Utc::now().timestamp();
+
+    let result = sqlx::query!(
+        r#"
+        UPDATE `flashes`
+        SET
+            `contents` = ?2,
+            `priority` = ?3,
+            `show_from` = ?4,
+            `expires_at` = ?5,
+            `recipient` = ?6,
+            `updated_at` = ?7
+        WHERE
+            `id` = ?1
+            AND `acknowledged` = 0
+        "#,
+        id,
+        params.contents,
+        priority,
+        params.show_from,
+        params.expires_at,
+        recipient,
+        now_timestamp,
+    )
+    .execute(&*pool)
+    .await?;
+
+    if result.rows_affected() == 0 {
+        let exists = sqlx::query("SELECT 1 FROM `flashes` WHERE `id` = ?1")
+            .bind(id)
+            .fetch_optional(&*pool)
+            .await?
+            .is_some();
+
+        return Err(match exists {
+            true => ApiError::conflict(format!("message {} is already dismissed", id)),
+            false => ApiError::not_found(format!("no message {}", id)),
+        }
+        .into());
+    }
+
+    let row = sqlx::query(
+        r#"
+        SELECT
+            `id`,
+            `contents`,
+            `created_at`,
+            `priority`,
+            `show_from`,
+            `expires_at`,
+            `author`,
+            `recipient`,
+            `updated_at`
+        FROM `flashes`
+        WHERE `id` = ?1
+        "#,
+    )
+    .bind(id)
+    .fetch_one(&*pool)
+    .await?;
+    let flash =
+        flash_from_row(&row).ok_or_else(|| anyhow!("message {} could not be read back", id))?;
+
+    events.publish(ApiEvent::FlashUpdated {
+        flash: flash.clone(),
+    });
+
+    Ok(flash)
 }
 
-// TODO: make this a common type
-#[derive(Serialize, Debug)]
-struct DismissFlashResponse {
-    success: bool,
-    error: Option<String>,
+/// Edit a message that hasn't been dismissed yet
+#[utoipa::path(
+    put,
+    path = "/api/flashes/{id}",
+    tag = "v1",
+    params(("id" = i64, Path, description = "ID of the message to edit")),
+    request_body(content = UpdateFlashParams, content_type = "application/x-www-form-urlencoded"),
+    responses((status = 200, body = UpdateFlashResponse))
+)]
+async fn update_flash(
+    Path(id): Path<i64>,
+    Form(params): Form<UpdateFlashParams>,
+    Extension(pool): Extension<Arc<SqlitePool>>,
+    Extension(config): Extension<Arc<Config>>,
+    Extension(events): Extension<Arc<EventBus>>,
+) -> Json<UpdateFlashResponse> {
+    match update_flash_impl(id, params, pool, &config, &events).await {
+        Ok(_) => Json(UpdateFlashResponse {
+            success: true,
+            error: None,
+        }),
+        Err(e) => Json(UpdateFlashResponse {
+            success: false,
+            error: Some(format!("failed to update flash: {}", ApiError::from(e))),
+        }),
+    }
 }
 
-async fn dismiss_flash_impl(params: DismissFlashParams, pool: Arc<SqlitePool>) -> Result<()> {
-    sqlx::query!(
+/// Removes a message outright, rather than dismissing it and keeping it in the history
+async fn delete_flash_impl(id: i64, pool: Arc<SqlitePool>, events: &EventBus) -> Result<()> {
+    let result = sqlx::query!("DELETE FROM `flashes` WHERE `id` = ?1", id)
+        .execute(&*pool)
+        .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(ApiError::not_found(format!("no message {}", id)).into());
+    }
+
+    events.publish(ApiEvent::FlashDeleted { id });
+
+    Ok(())
+}
+
+/// Delete a message, along with its history
+#[utoipa::path(
+    delete,
+    path = "/api/flashes/{id}",
+    tag = "v1",
+    params(("id" = i64, Path, description = "ID of the message to delete")),
+    responses((status = 200, body = DeleteFlashResponse))
+)]
+async fn delete_flash(
+    Path(id): Path<i64>,
+    Extension(pool): Extension<Arc<SqlitePool>>,
+    Extension(events): Extension<Arc<EventBus>>,
+) -> Json<DeleteFlashResponse> {
+    match delete_flash_impl(id, pool, &events).await {
+        Ok(()) => Json(DeleteFlashResponse {
+            success: true,
+            error: None,
+        }),
+        Err(e) => Json(DeleteFlashResponse {
+            success: false,
+            error: Some(format!("failed to delete flash: {}", ApiError::from(e))),
+        }),
+    }
+}
+
+async fn dismiss_flash_impl(
+    params: DismissFlashParams,
+    pool: Arc<SqlitePool>,
+    events: &EventBus,
+) -> Result<()> {
+    let mut member = member_name(params.member)?;
+
+    let recipient: Option<Option<String>> =
+        sqlx::query_scalar("SELECT `recipient` FROM `flashes` WHERE `id` = ?1")
+            .bind(params.id)
+            .fetch_optional(&*pool)
+            .await?;
+    let recipient = match recipient {
+        Some(recipient) => recipient,
+        None => return Err(ApiError::not_found(format!("no message {}", params.id)).into()),
+    };
+
+    if let Some(recipient) = recipient {
+        if !member
+            .as_deref()
+            .is_some_and(|member| member.eq_ignore_ascii_case(&recipient))
+        {
+            return Err(ApiError::forbidden(format!(
+                "message {} can only be dismissed by {}",
+                params.id, recipient
+            ))
+            .into());
+        }
+        member = Some(recipient);
+    }
+
+    let now_timestamp = Utc::now().timestamp();
+    let result = sqlx::query!(
         r#"
         UPDATE `flashes`
         SET
-            `acknowledged` = 1
+            `acknowledged` = 1,
+            `acknowledged_at` = ?2,
+            `acknowledged_by` = ?3
         WHERE
             `id` = ?1
+            AND `acknowledged` = 0
         "#,
         params.id,
+        now_timestamp,
+        member,
     )
     .execute(&*pool)
     .await?;
 
+    if result.rows_affected() == 0 {
+        return Err(
+            ApiError::conflict(format!("message {} is already dismissed", params.id)).into(),
+        );
+    }
+
+    events.publish(ApiEvent::FlashDismissed { id: params.id });
+
     Ok(())
 }
 
+/// Dismiss a message
+#[utoipa::path(
+    post,
+    path = "/api/flashes/dismiss",
+    tag = "v1",
+    params(RedirectParams),
+    request_body(content = DismissFlashParams, content_type = "application/x-www-form-urlencoded"),
+    responses(
+        (status = 200, body = DismissFlashResponse),
+        (status = 303, description = "Redirected to `redirect`, with any error in its `error` parameter")
+    )
+)]
 async fn dismiss_flash(
+    Query(redirect): Query<RedirectParams>,
     Form(params): Form<DismissFlashParams>,
     Extension(pool): Extension<Arc<SqlitePool>>,
     Extension(_config): Extension<Arc<Config>>,
-) -> Json<DismissFlashResponse> {
-    match dismiss_flash_impl(params, pool).await {
+    Extension(events): Extension<Arc<EventBus>>,
+) -> Response {
+    if let Some(rejection) = redirect.reject_invalid() {
+        return rejection;
+    }
+
+    let result = dismiss_flash_impl(params, pool, &events)
+        .await
+        .map_err(|e| format!("failed to dismiss flash: {}", ApiError::from(e)));
+
+    if let Some(response) = redirect.respond(result.clone().err()) {
+        return response;
+    }
+
+    match result {
         Ok(()) => Json(DismissFlashResponse {
             success: true,
             error: None,
         }),
-        Err(e) => Json(DismissFlashResponse {
+        Err(error) => Json(DismissFlashResponse {
             success: false,
-            error: Some(format!("failed to dismiss flash: {}", e)),
+            error: Some(error),
         }),
     }
+    .into_response()
 }
 
-#[derive(Debug, Serialize)]
-struct GetMetarsResponse {
-    stations: HashMap<String, StationMetar>,
+#[derive(Debug, Serialize, ToSchema)]
+struct GetCatchUpResponse {
+    report: Option<CatchUpReport>,
 }
 
-async fn get_metars(
-    Extension(_pool): Extension<Arc<SqlitePool>>,
-    Extension(config): Extension<Arc<Config>>,
-) -> Json<GetMetarsResponse> {
-    Json(GetMetarsResponse {
-        stations: build_metar_response(&config.metar_stations).await,
+/// Report on chores caught up with at startup, if the server had been down
+#[utoipa::path(
+    get,
+    path = "/api/catch_up",
+    tag = "v1",
+    responses((status = 200, body = GetCatchUpResponse))
+)]
+async fn get_catch_up(
+    Extension(catch_up_report): Extension<Arc<RwLock<Option<CatchUpReport>>>>,
+) -> Json<GetCatchUpResponse> {
+    Json(GetCatchUpResponse {
+        report: catch_up_report.read().await.clone(),
     })
 }
 
-async fn serve(pool: Arc<SqlitePool>, config: Arc<Config>) -> Result<()> {
-    let serve_dir = get_service(ServeDir::new("dist")).handle_error(handle_error);
+/// Current weather at each configured station
+#[utoipa::path(
+    get,
+    path = "/api/metars",
+    tag = "v1",
+    responses(
+        (status = 200, body = GetMetarsResponse),
+        (status = 500, description = "The cached observations couldn't be read", body = ApiErrorResponse)
+    )
+)]
+async fn get_metars(
+    Extension(pool): Extension<Arc<SqlitePool>>,
+    Extension(config): Extension<Arc<Config>>,
+) -> Result<Json<GetMetarsResponse>, ApiError> {
+    let stations = observations::cached_metars(&pool, &config).await?;
+
+    Ok(Json(GetMetarsResponse { stations }))
+}
 
+async fn serve(
+    pool: Arc<SqlitePool>,
+    config: Arc<Config>,
+    catch_up_report: Arc<RwLock<Option<CatchUpReport>>>,
+    events: Arc<EventBus>,
+    alerts: Arc<Alerts>,
+    assets: Arc<Assets>,
+) -> Result<()> {
     let cors = CorsLayer::new()
-        // allow `GET` and `POST` when accessing the resource
-        .allow_methods(vec![Method::GET, Method::POST])
+        // allow `GET`, `POST`, `PUT` and `DELETE` when accessing the resource
+        .allow_methods(vec![Method::GET, Method::POST, Method::PUT, Method::DELETE])
         // allow requests from any origin
         .allow_origin(Any);
 
     let app = Router::new()
-        .route("/", get(index))
-        .nest("/dist", serve_dir.clone())
+        .route("/", get(assets::index))
+        .route("/dist/*path", get(assets::dist))
+        .route("/html", get(html::dashboard))
         .route("/api/chores", get(list_chores))
+        .route("/api/chores/archived", get(list_archived_chores))
         .route("/api/chores/complete", post(complete_chore))
         .route("/api/flashes", get(get_flashes))
         .route("/api/flashes", post(add_flash))
         .route("/api/flashes/dismiss", post(dismiss_flash))
+        .route("/api/flashes/history", get(flash_history))
+        .route("/api/flashes/:id", put(update_flash))
+        .route("/api/flashes/:id", delete(delete_flash))
         .route("/api/metars", get(get_metars))
+        .route("/api/catch_up", get(get_catch_up))
+        .route("/api/dashboard.png", get(dashboard_image::dashboard_png))
+        .route("/api/events", get(events::events))
+        .route("/api/openapi.json", get(openapi::openapi_json))
+        .route("/api/docs", get(openapi::docs))
+        .nest("/api/v2", v2::routes())
+        .fallback(v2::not_found.into_service())
         .layer(Extension(pool))
+        .layer(Extension(catch_up_report))
         .layer(Extension(config.clone()))
+        .layer(Extension(events))
+        .layer(Extension(alerts))
+        .layer(Extension(assets))
+        .layer(Extension(Arc::new(DashboardImageCache::default())))
+        // event streams must be flushed as they're written rather than buffered by the encoder
+        .layer(CompressionLayer::new().compress_when(
+            DefaultPredicate::new().and(NotForContentType::const_new("text/event-stream")),
+        ))
         .layer(cors);
 
     let addr = SocketAddr::from(([0, 0, 0, 0], config.port));
@@ -669,6 +1998,43 @@ async fn serve(pool: Arc<SqlitePool>, config: Arc<Config>) -> Result<()> {
     Ok(())
 }
 
+async fn run_server(config_path: &str, assets_dir: Option<PathBuf>) -> Result<()> {
+    tracing_subscriber::fmt::init();
+
+    let config = Config::from_path(config_path)?;
+    tracing::info!("Evaluating schedules in {}", config.timezone);
+
+    let pool = Arc::new(SqlitePool::connect(&std::env::var("DATABASE_URL")?).await?);
+    sqlx::migrate!().run(&*pool).await?;
+    apply_chore_aliases(&pool, &config).await?;
+
+    let catch_up_report = Arc::new(RwLock::new(None));
+    let events = EventBus::new();
+    let alerts = Alerts::new(pool.clone(), config.clone(), events.clone());
+
+    try_join!(
+        update_chores(
+            pool.clone(),
+            config.clone(),
+            catch_up_report.clone(),
+            events.clone(),
+            alerts.clone()
+        ),
+        alerts.clone().watch_chores(),
+        observations::poll_weather(pool.clone(), config.clone(), events.clone(), alerts.clone()),
+        serve(
+            pool.clone(),
+            config.clone(),
+            catch_up_report.clone(),
+            events.clone(),
+            alerts.clone(),
+            Assets::load(assets_dir)
+        ),
+    )?;
+
+    Ok(())
+}
+
 #[tokio::main]
 async fn main() -> Result<()> {
     let default_panic = std::panic::take_hook();
@@ -679,17 +2045,505 @@ async fn main() -> Result<()> {
 
     let args = Args::parse();
 
-    let config = Config::from_path(&args.config_path)?;
+    match args.command.unwrap_or(Command::Serve { assets_dir: None }) {
+        Command::Serve { assets_dir } => run_server(&args.config_path, assets_dir).await,
+        Command::Check => {
+            let problems = check::check_config(&args.config_path)?;
+            if problems.is_empty() {
+                println!("{}: OK", args.config_path);
+                return Ok(());
+            }
 
-    tracing_subscriber::fmt::init();
+            for problem in problems.iter() {
+                println!("{}: {}", args.config_path, problem);
+            }
 
-    let pool = Arc::new(SqlitePool::connect(&std::env::var("DATABASE_URL")?).await?);
-    sqlx::migrate!().run(&*pool).await?;
+            Err(anyhow!("found {} problem(s)", problems.len()))
+        }
+        Command::Preview { days, format } => {
+            let config = Config::from_path(&args.config_path)?;
+            let chores = preview::preview_chores(&config, days)?;
 
-    try_join!(
-        update_chores(pool.clone(), config.clone()),
-        serve(pool.clone(), config.clone()),
-    )?;
+            match format {
+                OutputFormat::Table => preview::print_table(&chores),
+                OutputFormat::Json => preview::print_json(&chores)?,
+            }
 
-    Ok(())
+            Ok(())
+        }
+        Command::Client(client_args) => client::run(client_args).await,
+        Command::Tui(tui_args) => tui::run(tui_args, &args.config_path).await,
+        Command::Openapi { typescript } => {
+            match typescript {
+                true => print!("{}", openapi::typescript()),
+                false => print!("{}", openapi::spec_json()),
+            }
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A fresh in-memory database. Every connection would open its own, so there's only one
+    pub(crate) async fn memory_pool() -> SqlitePool {
+        let pool = sqlx::sqlite::SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        sqlx::migrate!().run(&pool).await.unwrap();
+        pool
+    }
+
+    fn status(result: Result<()>) -> Option<axum::http::StatusCode> {
+        result
+            .err()
+            .map(|e| e.downcast::<ApiError>().unwrap().status)
+    }
+
+    #[test]
+    fn validates_flash_length() {
+        let longest = "a".repeat(MAX_FLASH_LENGTH);
+        assert_eq!(status(validate_flash(&longest, None, None)), None);
+
+        // counted in characters, not bytes
+        let longest = "é".repeat(MAX_FLASH_LENGTH);
+        assert_eq!(status(validate_flash(&longest, None, None)), None);
+
+        let too_long = "a".repeat(MAX_FLASH_LENGTH + 1);
+        assert_eq!(
+            status(validate_flash(&too_long, None, None)),
+            Some(axum::http::StatusCode::BAD_REQUEST)
+        );
+    }
+
+    #[tokio::test]
+    async fn posts_latest_recurring_flash_after_downtime() {
+        let pool = memory_pool().await;
+        let recurring = RecurringFlash {
+            contents: "Trash pickup tomorrow".to_string(),
+            frequency: "daily at 9:00 and 21:00".parse().unwrap(),
+            priority: FlashPriority::Info,
+            auto_acknowledge: None,
+        };
+
+        let at = |day, hour| Tz::UTC.with_ymd_and_hms(2026, 10, day, hour, 0, 0).unwrap();
+        // down from the 15th to noon on the 18th, looking a day ahead
+        let (last_update, now, lookahead) = (at(15, 12), at(18, 12), at(19, 12));
+
+        let mut txn = pool.begin().await.unwrap();
+        let posted = post_recurring_flashes(
+            &mut txn,
+            "trash",
+            &recurring,
+            &last_update,
+            &now,
+            &lookahead,
+        )
+        .await
+        .unwrap();
+        let times: Vec<_> = posted.iter().map(|flash| flash.created_at).collect();
+        assert_eq!(
+            times,
+            [at(18, 9), at(18, 21), at(19, 9)].map(|time| time.timestamp())
+        );
+        assert!(posted
+            .iter()
+            .all(|flash| flash.show_from == Some(flash.created_at)));
+
+        // the next pass doesn't post them again
+        let posted = post_recurring_flashes(
+            &mut txn,
+            "trash",
+            &recurring,
+            &now,
+            &(now + Duration::hours(1)),
+            &(lookahead + Duration::hours(1)),
+        )
+        .await
+        .unwrap();
+        assert!(posted.is_empty());
+
+        // nor once one that's coming up is deleted
+        sqlx::query("DELETE FROM `flashes` WHERE `recurring_time` = ?1")
+            .bind(at(19, 9).timestamp())
+            .execute(&mut *txn)
+            .await
+            .unwrap();
+        let posted = post_recurring_flashes(
+            &mut txn,
+            "trash",
+            &recurring,
+            &(now + Duration::hours(1)),
+            &(now + Duration::hours(2)),
+            &(lookahead + Duration::hours(2)),
+        )
+        .await
+        .unwrap();
+        assert!(posted.is_empty());
+        txn.commit().await.unwrap();
+
+        let stored: Vec<(i64, Option<i64>, i64)> = sqlx::query_as(
+            "SELECT `created_at`, `show_from`, `recurring_time` FROM `flashes` ORDER BY `id`",
+        )
+        .fetch_all(&pool)
+        .await
+        .unwrap();
+        assert_eq!(
+            stored,
+            [at(18, 9), at(18, 21)].map(|time| {
+                let time = time.timestamp();
+                (time, Some(time), time)
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn rejects_invalid_redirect_before_acting() {
+        let pool = Arc::new(memory_pool().await);
+        let config: Config = serde_json::from_value(serde_json::json!({
+            "chores": {},
+            "metar_stations": [],
+            "overdue_time": "1h",
+        }))
+        .unwrap();
+
+        let response = add_flash(
+            Query(serde_urlencoded::from_str("redirect=//evil.example").unwrap()),
+            Form(AddFlashParams {
+                contents: "Plumber coming Thursday".to_string(),
+                ..Default::default()
+            }),
+            Extension(pool.clone()),
+            Extension(Arc::new(config)),
+            Extension(EventBus::new()),
+        )
+        .await;
+        assert_eq!(response.status(), axum::http::StatusCode::BAD_REQUEST);
+
+        let flashes: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM `flashes`")
+            .fetch_one(&*pool)
+            .await
+            .unwrap();
+        assert_eq!(flashes, 0);
+    }
+
+    fn chores_config(catch_up: &str) -> Config {
+        serde_json::from_value(serde_json::json!({
+            "chores": {
+                "dishes": {
+                    "description": "Wash the dishes",
+                    "frequency": "daily at 9:00 and 21:00",
+                    "catch_up": catch_up,
+                },
+            },
+            "metar_stations": [],
+            "overdue_time": "1h",
+        }))
+        .unwrap()
+    }
+
+    /// `(expected_completion_time, status)` of every instance, oldest first
+    async fn instances(pool: &SqlitePool) -> Vec<(i64, String)> {
+        sqlx::query_as(
+            "SELECT `expected_completion_time`, `status` FROM `chores` ORDER BY `expected_completion_time`",
+        )
+        .fetch_all(pool)
+        .await
+        .unwrap()
+    }
+
+    /// Runs a pass after the server was down for three days, returning what it reported
+    async fn catch_up_after_outage(pool: &Arc<SqlitePool>, config: &Config) -> CatchUpReport {
+        let last_update = config.now().timestamp() - 3 * 86400;
+        sqlx::query("INSERT INTO `updates` (`update_timestamp`) VALUES (?1)")
+            .bind(last_update)
+            .execute(&**pool)
+            .await
+            .unwrap();
+
+        let report = RwLock::new(None);
+        update_chores_once(pool, config, &report, &EventBus::new(), true)
+            .await
+            .unwrap();
+        let report = report.into_inner().unwrap();
+        assert_eq!(report.last_update, last_update);
+        report
+    }
+
+    fn missed(instances: &[(i64, String)]) -> usize {
+        instances
+            .iter()
+            .filter(|(_, status)| status == "missed")
+            .count()
+    }
+
+    #[tokio::test]
+    async fn backfills_chores_missed_during_outage() {
+        let pool = Arc::new(memory_pool().await);
+        let config = chores_config("backfill");
+        let report = catch_up_after_outage(&pool, &config).await;
+
+        // twice a day for three days, less the one still open
+        let caught_up = &report.chores[0];
+        assert!((5..=6).contains(&caught_up.missed_occurrences));
+        assert_eq!(caught_up.backfilled, caught_up.missed_occurrences);
+        assert_eq!(
+            missed(&instances(&pool).await),
+            caught_up.missed_occurrences
+        );
+
+        let contents: String = sqlx::query_scalar("SELECT `contents` FROM `flashes`")
+            .fetch_one(&*pool)
+            .await
+            .unwrap();
+        assert!(contents.starts_with("Caught up on chores missed during downtime: dishes"));
+    }
+
+    #[tokio::test]
+    async fn collapses_chores_missed_during_outage() {
+        let pool = Arc::new(memory_pool().await);
+        let config = chores_config("collapse");
+        let report = catch_up_after_outage(&pool, &config).await;
+
+        let caught_up = &report.chores[0];
+        assert!((5..=6).contains(&caught_up.missed_occurrences));
+        assert_eq!(caught_up.backfilled, 1);
+        assert_eq!(missed(&instances(&pool).await), 1);
+    }
+
+    #[tokio::test]
+    async fn skips_chores_missed_during_outage() {
+        let pool = Arc::new(memory_pool().await);
+        let config = chores_config("skip");
+        let report = catch_up_after_outage(&pool, &config).await;
+
+        let caught_up = &report.chores[0];
+        assert!((5..=6).contains(&caught_up.missed_occurrences));
+        assert_eq!(caught_up.backfilled, 0);
+
+        let instances = instances(&pool).await;
+        assert_eq!(missed(&instances), 0);
+        // the open instance and the ones ahead are still scheduled
+        assert!(!instances.is_empty());
+    }
+
+    #[tokio::test]
+    async fn catch_up_policy_only_applies_after_startup() {
+        let pool = Arc::new(memory_pool().await);
+        let config = chores_config("skip");
+        let last_update = config.now().timestamp() - 3 * 86400;
+        sqlx::query("INSERT INTO `updates` (`update_timestamp`) VALUES (?1)")
+            .bind(last_update)
+            .execute(&*pool)
+            .await
+            .unwrap();
+
+        // a later pass, such as one after the scheduler failed for a while, records every instance
+        let report = RwLock::new(None);
+        update_chores_once(&pool, &config, &report, &EventBus::new(), false)
+            .await
+            .unwrap();
+        assert!(report.into_inner().is_none());
+        assert!((5..=6).contains(&missed(&instances(&pool).await)));
+    }
+
+    fn config_with_chores(titles: &[&str]) -> Config {
+        let chores: serde_json::Map<_, _> = titles
+            .iter()
+            .map(|title| {
+                let chore = serde_json::json!({
+                    "description": "",
+                    "frequency": "daily at 9:00",
+                });
+                (title.to_string(), chore)
+            })
+            .collect();
+
+        serde_json::from_value(serde_json::json!({
+            "chores": chores,
+            "metar_stations": [],
+            "overdue_time": "1h",
+        }))
+        .unwrap()
+    }
+
+    async fn insert_instance(pool: &SqlitePool, title: &str, time: i64, status: &str) {
+        sqlx::query(
+            r#"
+            INSERT INTO `chores`
+            (`title`, `expected_completion_time`, `status`, `overdue_time`, `expiration_time`)
+            VALUES (?1, ?2, ?3, ?2 + 3600, ?2 + 86400)
+            "#,
+        )
+        .bind(title)
+        .bind(time)
+        .bind(status)
+        .execute(pool)
+        .await
+        .unwrap();
+    }
+
+    async fn statuses(pool: &SqlitePool) -> Vec<(String, i64, String)> {
+        sqlx::query_as(
+            "SELECT `title`, `expected_completion_time`, `status` FROM `chores` ORDER BY `title`, `expected_completion_time`",
+        )
+        .fetch_all(pool)
+        .await
+        .unwrap()
+    }
+
+    const NOW: i64 = 1_800_000_000;
+
+    /// "dishes" is still configured, "trash" has an old missed instance, one that just expired and
+    /// one still due
+    async fn history() -> Arc<SqlitePool> {
+        let pool = Arc::new(memory_pool().await);
+        insert_instance(&pool, "dishes", NOW + 3600, "assigned").await;
+        insert_instance(&pool, "trash", NOW - 3 * 86400, "missed").await;
+        insert_instance(&pool, "trash", NOW - 86400 - 60, "assigned").await;
+        insert_instance(&pool, "trash", NOW + 3600, "assigned").await;
+        pool
+    }
+
+    #[tokio::test]
+    async fn cancels_removed_chores() {
+        let pool = history().await;
+        let config = config_with_chores(&["dishes"]);
+
+        let mut txn = pool.begin().await.unwrap();
+        let events = cancel_orphaned_chores(&mut txn, &config, NOW)
+            .await
+            .unwrap();
+        txn.commit().await.unwrap();
+
+        assert_eq!(events.len(), 1);
+        assert_eq!(
+            statuses(&pool).await,
+            [
+                ("dishes".to_string(), NOW + 3600, "assigned".to_string()),
+                ("trash".to_string(), NOW - 3 * 86400, "missed".to_string()),
+                // already expired, so it's left to be marked missed
+                (
+                    "trash".to_string(),
+                    NOW - 86400 - 60,
+                    "assigned".to_string()
+                ),
+                ("trash".to_string(), NOW + 3600, "cancelled".to_string()),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn restores_readded_chores() {
+        let pool = history().await;
+
+        let mut txn = pool.begin().await.unwrap();
+        cancel_orphaned_chores(&mut txn, &config_with_chores(&["dishes"]), NOW)
+            .await
+            .unwrap();
+        let config = config_with_chores(&["dishes", "trash"]);
+        assert!(cancel_orphaned_chores(&mut txn, &config, NOW)
+            .await
+            .unwrap()
+            .is_empty());
+        let events = restore_readded_chores(&mut txn, &config, NOW)
+            .await
+            .unwrap();
+        txn.commit().await.unwrap();
+
+        assert_eq!(events.len(), 1);
+        assert_eq!(
+            statuses(&pool).await[3],
+            ("trash".to_string(), NOW + 3600, "assigned".to_string())
+        );
+    }
+
+    #[tokio::test]
+    async fn lists_archived_chores() {
+        let pool = history().await;
+        let mut txn = pool.begin().await.unwrap();
+        cancel_orphaned_chores(&mut txn, &config_with_chores(&["dishes"]), NOW)
+            .await
+            .unwrap();
+        txn.commit().await.unwrap();
+
+        let archived =
+            list_archived_chores_impl(pool.clone(), Arc::new(config_with_chores(&["dishes"])))
+                .await
+                .unwrap();
+        assert_eq!(archived.len(), 1);
+        assert_eq!(archived[0].title, "trash");
+        let instances: Vec<_> = archived[0]
+            .instances
+            .iter()
+            .map(|instance| (instance.expected_completion_time, instance.status.clone()))
+            .collect();
+        assert!(matches!(
+            instances.as_slice(),
+            [
+                (_, ApiStatus::Missed),
+                (_, ApiStatus::Assigned),
+                (_, ApiStatus::Cancelled)
+            ]
+        ));
+        assert!(instances.windows(2).all(|pair| pair[0].0 < pair[1].0));
+
+        let archived = list_archived_chores_impl(
+            pool.clone(),
+            Arc::new(config_with_chores(&["dishes", "trash"])),
+        )
+        .await
+        .unwrap();
+        assert!(archived.is_empty());
+    }
+
+    #[tokio::test]
+    async fn completes_chores_by_id_or_title() {
+        let pool = history().await;
+        let events = EventBus::new();
+        let complete = |id, title: Option<&str>, expected_completion_time| {
+            complete_chore_impl(
+                CompleteChoreParams {
+                    id,
+                    title: title.map(str::to_string),
+                    expected_completion_time,
+                },
+                pool.clone(),
+                &events,
+            )
+        };
+
+        let instance = complete(Some(1), None, None).await.unwrap();
+        assert_eq!(
+            (instance.title.as_str(), instance.expected_completion_time),
+            ("dishes", NOW + 3600)
+        );
+
+        let instance = complete(None, Some("trash"), Some(NOW - 3 * 86400))
+            .await
+            .unwrap();
+        assert_eq!(instance.id, 2);
+
+        let status = |result: Result<ChoreInstance>| {
+            result
+                .err()
+                .map(|e| e.downcast::<ApiError>().unwrap().status)
+        };
+        assert_eq!(
+            status(complete(Some(1), None, None).await),
+            Some(axum::http::StatusCode::CONFLICT)
+        );
+        assert_eq!(
+            status(complete(Some(99), None, None).await),
+            Some(axum::http::StatusCode::NOT_FOUND)
+        );
+        assert_eq!(
+            status(complete(None, Some("trash"), None).await),
+            Some(axum::http::StatusCode::BAD_REQUEST)
+        );
+    }
 }
@@ -0,0 +1,114 @@
+use std::collections::HashSet;
+use std::sync::OnceLock;
+
+use ammonia::{Builder, UrlRelative};
+use pulldown_cmark::{html, Options, Parser};
+
+/// Tags that survive sanitizing. Images, tables and headings are left out, as they'd overwhelm a
+/// card on the dashboard
+const ALLOWED_TAGS: &[&str] = &[
+    "a",
+    "blockquote",
+    "br",
+    "code",
+    "del",
+    "em",
+    "hr",
+    "li",
+    "ol",
+    "p",
+    "pre",
+    "strong",
+    "ul",
+];
+
+const ALLOWED_URL_SCHEMES: &[&str] = &["http", "https", "mailto", "tel"];
+
+fn sanitizer() -> &'static Builder<'static> {
+    static SANITIZER: OnceLock<Builder<'static>> = OnceLock::new();
+
+    SANITIZER.get_or_init(|| {
+        let mut builder = Builder::empty();
+        builder
+            .tags(ALLOWED_TAGS.iter().copied().collect())
+            .tag_attributes([("a", ["href"].into_iter().collect())].into())
+            .url_schemes(ALLOWED_URL_SCHEMES.iter().copied().collect::<HashSet<_>>())
+            // relative links would point somewhere on the dashboard's own server
+            .url_relative(UrlRelative::Deny)
+            // links open outside the dashboard, without giving the page they open a handle on it
+            .link_rel(Some("noopener noreferrer nofollow"))
+            .set_tag_attribute_value("a", "target", "_blank");
+        builder
+    })
+}
+
+/// Renders Markdown from flashes and chore descriptions to HTML that's safe to insert into the
+/// dashboard as is
+pub fn render(source: &str) -> String {
+    let parser = Parser::new_ext(source, Options::ENABLE_STRIKETHROUGH);
+
+    let mut unsafe_html = String::new();
+    html::push_html(&mut unsafe_html, parser);
+
+    sanitizer().clean(&unsafe_html).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn drops_script_and_style_content() {
+        let html = render("before <script>alert(1)</script><style>p { color: red }</style> after");
+        assert!(!html.contains("script"), "{}", html);
+        assert!(!html.contains("alert"), "{}", html);
+        assert!(!html.contains("style"), "{}", html);
+        assert!(!html.contains("color"), "{}", html);
+        assert!(
+            html.contains("before") && html.contains("after"),
+            "{}",
+            html
+        );
+    }
+
+    #[test]
+    fn strips_event_handlers_and_script_links() {
+        let html = render("<img src=\"x\" onerror=\"alert(1)\">");
+        assert!(!html.contains("img"), "{}", html);
+        assert!(!html.contains("onerror"), "{}", html);
+
+        let html = render("[x](javascript:alert(1))");
+        assert!(!html.contains("javascript"), "{}", html);
+        assert!(!html.contains("href"), "{}", html);
+    }
+
+    #[test]
+    fn drops_relative_links_and_disallowed_tags() {
+        let html = render("[settings](/api/flashes) [up](../admin)");
+        assert!(!html.contains("href"), "{}", html);
+        assert!(html.contains("settings") && html.contains("up"), "{}", html);
+
+        let html = render("# Heading\n\n<iframe src=\"https://example.com\"></iframe><table><tr><td>cell</td></tr></table>");
+        for tag in ["<h1", "<iframe", "<table", "<td"] {
+            assert!(!html.contains(tag), "{} in {}", tag, html);
+        }
+    }
+
+    #[test]
+    fn opens_links_in_a_new_tab() {
+        assert_eq!(
+            render("[manual](https://example.com/manual)"),
+            "<p><a href=\"https://example.com/manual\" target=\"_blank\" \
+             rel=\"noopener noreferrer nofollow\">manual</a></p>\n"
+        );
+    }
+
+    #[test]
+    fn keeps_basic_formatting() {
+        assert_eq!(
+            render("**bold**\n\n- one\n- two\n\n`code`"),
+            "<p><strong>bold</strong></p>\n<ul>\n<li>one</li>\n<li>two</li>\n</ul>\n\
+             <p><code>code</code></p>\n"
+        );
+    }
+}
@@ -0,0 +1,245 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use anyhow::Result;
+use chores::weather::{fetch_metars, StationMetar};
+use chrono::Utc;
+use sqlx::SqlitePool;
+
+use crate::alerts::Alerts;
+use crate::events::EventBus;
+use crate::Config;
+
+/// Replaces a station's cached observation with one that was just fetched
+async fn store_observation(pool: &SqlitePool, station: &str, metar: &StationMetar) -> Result<()> {
+    let decoded = serde_json::to_string(metar)?;
+    let fetched_at = metar.fetched_at.unwrap_or_else(|| Utc::now().timestamp());
+
+    sqlx::query!(
+        r#"
+        INSERT INTO `observations`
+        (
+            `station`,
+            `metar`,
+            `decoded`,
+            `observed_at`,
+            `fetched_at`
+        )
+        VALUES
+        (
+            ?1,
+            ?2,
+            ?3,
+            ?4,
+            ?5
+        )
+        ON CONFLICT (`station`) DO UPDATE SET
+            `metar` = excluded.`metar`,
+            `decoded` = excluded.`decoded`,
+            `observed_at` = excluded.`observed_at`,
+            `fetched_at` = excluded.`fetched_at`
+        "#,
+        station,
+        metar.metar,
+        decoded,
+        metar.observed_at,
+        fetched_at,
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// The last good observation at each configured station, marked stale once it's older than
+/// `metar_stale_after`. Stations that have never been fetched are left out
+pub async fn cached_metars(
+    pool: &SqlitePool,
+    config: &Config,
+) -> Result<HashMap<String, StationMetar>> {
+    let rows = sqlx::query!(
+        r#"
+        SELECT
+            `station`,
+            `decoded`,
+            `observed_at`,
+            `fetched_at`
+        FROM `observations`
+        "#,
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let now_timestamp = Utc::now().timestamp();
+    let stale_after = config.metar_stale_after.as_secs() as i64;
+
+    let mut metars = HashMap::new();
+    for row in rows {
+        let station = row.station;
+        // the station may have been removed from the config since
+        if !config
+            .metar_stations
+            .iter()
+            .any(|metar_station| metar_station.id() == station)
+        {
+            continue;
+        }
+
+        let mut metar: StationMetar = match serde_json::from_str(&row.decoded) {
+            Ok(metar) => metar,
+            Err(e) => {
+                tracing::warn!("Failed to decode cached METAR for {}: {}", station, e);
+                continue;
+            }
+        };
+
+        metar.observed_at = row.observed_at;
+        metar.fetched_at = Some(row.fetched_at);
+        metar.stale = now_timestamp - metar.observed_at.unwrap_or(row.fetched_at) > stale_after;
+
+        metars.insert(station, metar);
+    }
+
+    Ok(metars)
+}
+
+/// Fetches every station once, caching the observations that could be fetched and raising alerts
+/// for the rest
+async fn poll_weather_once(
+    pool: &SqlitePool,
+    config: &Config,
+    events: &EventBus,
+    alerts: &Alerts,
+) -> Result<()> {
+    let (fetched, failures) = fetch_metars(&config.metar_stations, &config.weather_source).await;
+    alerts.weather_fetched(&failures).await;
+
+    for (station, metar) in fetched.iter() {
+        store_observation(pool, station, metar).await?;
+    }
+
+    events.weather_fetched(&cached_metars(pool, config).await?);
+
+    Ok(())
+}
+
+/// Keeps the cached observations up to date, fetching every `metar_poll_interval`
+pub async fn poll_weather(
+    pool: Arc<SqlitePool>,
+    config: Arc<Config>,
+    events: Arc<EventBus>,
+    alerts: Arc<Alerts>,
+) -> Result<()> {
+    loop {
+        if let Err(e) = poll_weather_once(&pool, &config, &events, &alerts).await {
+            tracing::error!("Failed to update the weather: {}", e);
+        }
+
+        tokio::time::sleep(config.metar_poll_interval).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::{Path, PathBuf};
+
+    use super::*;
+    use crate::tests::memory_pool;
+
+    /// A directory for a file source to read `<station>.TXT` from
+    fn station_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("chores-{}-{}", name, std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn fixture(station: &str) -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("fixtures/metar")
+            .join(format!("{}.TXT", station))
+    }
+
+    fn config(dir: &Path, stations: &[&str], stale_after: &str) -> Config {
+        serde_json::from_value(serde_json::json!({
+            "chores": {},
+            "metar_stations": stations,
+            "weather_source": { "type": "file", "path": dir },
+            "metar_stale_after": stale_after,
+            "overdue_time": "1h",
+        }))
+        .unwrap()
+    }
+
+    async fn poll(pool: &Arc<SqlitePool>, config: Config) {
+        let config = Arc::new(config);
+        let events = EventBus::new();
+        let alerts = Alerts::new(pool.clone(), config.clone(), events.clone());
+        poll_weather_once(pool, &config, &events, &alerts)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn replaces_cached_observations() {
+        let pool = memory_pool().await;
+        let mut metar: StationMetar = serde_json::from_value(serde_json::json!({
+            "metar": "KDEN 181753Z 00000KT 10SM CLR 10/M05 A3012",
+            "weather": [],
+            "observed_at": 100,
+            "fetched_at": 200,
+        }))
+        .unwrap();
+        store_observation(&pool, "KDEN", &metar).await.unwrap();
+
+        metar.metar = "KDEN 181853Z 00000KT 10SM CLR 11/M05 A3011".to_string();
+        metar.observed_at = Some(3700);
+        metar.fetched_at = Some(3800);
+        store_observation(&pool, "KDEN", &metar).await.unwrap();
+
+        let rows: Vec<(String, String, i64)> =
+            sqlx::query_as("SELECT `station`, `metar`, `fetched_at` FROM `observations`")
+                .fetch_all(&pool)
+                .await
+                .unwrap();
+        assert_eq!(rows, [("KDEN".to_string(), metar.metar, 3800)]);
+    }
+
+    #[tokio::test]
+    async fn keeps_last_good_observation() {
+        let pool = Arc::new(memory_pool().await);
+        let dir = station_dir("observations");
+        std::fs::copy(fixture("KEEN"), dir.join("KEEN.TXT")).unwrap();
+
+        poll(&pool, config(&dir, &["KEEN"], "100000d")).await;
+        let fetched = cached_metars(&pool, &config(&dir, &["KEEN"], "100000d"))
+            .await
+            .unwrap();
+        let metar = &fetched["KEEN"];
+        assert!(metar.metar.starts_with("KEEN "));
+        assert!(metar.observed_at.is_some());
+        assert!(!metar.stale);
+
+        // the station can't be reached, so the last observation is still served
+        std::fs::remove_file(dir.join("KEEN.TXT")).unwrap();
+        poll(&pool, config(&dir, &["KEEN"], "100000d")).await;
+        let cached = cached_metars(&pool, &config(&dir, &["KEEN"], "100000d"))
+            .await
+            .unwrap();
+        assert_eq!(cached["KEEN"].metar, metar.metar);
+        assert_eq!(cached["KEEN"].fetched_at, metar.fetched_at);
+
+        // until it's older than `metar_stale_after`
+        let cached = cached_metars(&pool, &config(&dir, &["KEEN"], "1s"))
+            .await
+            .unwrap();
+        assert!(cached["KEEN"].stale);
+
+        // and stations removed from the config are left out
+        let cached = cached_metars(&pool, &config(&dir, &["KBOS"], "100000d"))
+            .await
+            .unwrap();
+        assert!(cached.is_empty());
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,362 @@
+use std::fmt::Write as _;
+
+use axum::response::Html;
+use axum::Json;
+use serde_json::Value;
+use utoipa::OpenApi;
+
+use crate::html::escape;
+
+const STYLE: &str = "
+body { font-family: sans-serif; max-width: 60em; margin: 1em auto; padding: 0 1em; }
+.endpoint { border: 1px solid #ccc; border-radius: 4px; margin: 1em 0; padding: 0.5em 1em; }
+.method { display: inline-block; min-width: 4em; font-weight: bold; text-transform: uppercase; }
+.tag { color: #666; font-size: 0.8em; }
+pre { background: #f4f4f4; padding: 0.5em; overflow-x: auto; }
+table { border-collapse: collapse; }
+td, th { text-align: left; padding: 0.2em 0.8em 0.2em 0; vertical-align: top; }
+";
+
+#[derive(OpenApi)]
+#[openapi(
+    info(description = "Chore scheduling, dashboard messages and weather"),
+    paths(
+        crate::list_chores,
+        crate::list_archived_chores,
+        crate::complete_chore,
+        crate::get_flashes,
+        crate::add_flash,
+        crate::dismiss_flash,
+        crate::flash_history,
+        crate::update_flash,
+        crate::delete_flash,
+        crate::get_metars,
+        crate::get_catch_up,
+        crate::events::events,
+        crate::dashboard_image::dashboard_png,
+        crate::v2::list_chores,
+        crate::v2::list_archived_chores,
+        crate::v2::complete_chore,
+        crate::v2::list_flashes,
+        crate::v2::add_flash,
+        crate::v2::dismiss_flash,
+        crate::v2::flash_history,
+        crate::v2::update_flash,
+        crate::v2::delete_flash,
+        crate::v2::get_metars,
+        crate::v2::get_catch_up,
+    ),
+    components(schemas(crate::dashboard_image::Palette)),
+    tags(
+        (name = "v1", description = "Failures are reported with `success: false` and a 200"),
+        (name = "v2", description = "Failures are reported with an `ApiErrorResponse` and a matching status code"),
+    )
+)]
+pub struct ApiDoc;
+
+/// The spec as checked in at `openapi.json`, so clients can be generated without running the server
+pub fn spec_json() -> String {
+    let mut json = ApiDoc::openapi()
+        .to_pretty_json()
+        .expect("the spec should always serialize");
+    json.push('\n');
+    json
+}
+
+/// Global TypeScript declarations for the spec's schemas, as checked in at `assets/ts/api.d.ts`.
+/// They aren't exported, since the frontend is loaded as a plain script rather than a module
+pub fn typescript() -> String {
+    let spec = serde_json::to_value(ApiDoc::openapi()).expect("the spec should always serialize");
+
+    let mut ts = String::from(
+        "// Generated from the OpenAPI spec by `chores openapi --typescript`. Don't edit by hand\n",
+    );
+    for (name, schema) in spec
+        .pointer("/components/schemas")
+        .and_then(Value::as_object)
+        .into_iter()
+        .flatten()
+    {
+        ts.push('\n');
+        write_ts_doc(&mut ts, schema, "");
+        let _ = writeln!(ts, "type {} = {};", name, ts_type(schema, ""));
+    }
+
+    ts
+}
+
+fn write_ts_doc(ts: &mut String, schema: &Value, indent: &str) {
+    let description = match schema.get("description").and_then(Value::as_str) {
+        Some(description) => description,
+        None => return,
+    };
+
+    let _ = writeln!(ts, "{}/**", indent);
+    for line in description.lines() {
+        let line = line.replace("*/", "*\\/");
+        let _ = writeln!(ts, "{} * {}", indent, line);
+    }
+    let _ = writeln!(ts, "{} */", indent);
+}
+
+/// The TypeScript for a schema, with any object literals indented to follow `indent`
+fn ts_type(schema: &Value, indent: &str) -> String {
+    if let Some(name) = schema_name(schema) {
+        return name.to_string();
+    }
+
+    if let Some(variants) = schema.get("oneOf").and_then(Value::as_array) {
+        let inner = format!("{}  ", indent);
+        let variants: Vec<String> = variants
+            .iter()
+            .map(|variant| ts_type(variant, &inner))
+            .collect();
+        return match variants.iter().any(|variant| variant.contains('\n')) {
+            true => variants
+                .iter()
+                .map(|variant| format!("\n{}| {}", inner, variant))
+                .collect(),
+            false => variants.join(" | "),
+        };
+    }
+
+    if let Some(parts) = schema.get("allOf").and_then(Value::as_array) {
+        let parts: Vec<String> = parts.iter().map(|part| ts_type(part, indent)).collect();
+        return parts.join(" & ");
+    }
+
+    if let Some(values) = schema.get("enum").and_then(Value::as_array) {
+        let values: Vec<String> = values.iter().map(Value::to_string).collect();
+        return values.join(" | ");
+    }
+
+    let types: Vec<&str> = match &schema["type"] {
+        Value::String(name) => vec![name.as_str()],
+        Value::Array(names) => names.iter().filter_map(Value::as_str).collect(),
+        _ => return "unknown".to_string(),
+    };
+    let types: Vec<String> = types
+        .into_iter()
+        .map(|name| match name {
+            "string" => "string".to_string(),
+            "integer" | "number" => "number".to_string(),
+            "boolean" => "boolean".to_string(),
+            "null" => "null".to_string(),
+            "array" => {
+                let item = ts_type(&schema["items"], indent);
+                match item.contains(" | ") && !item.starts_with('{') {
+                    true => format!("({})[]", item),
+                    false => format!("{}[]", item),
+                }
+            }
+            "object" => ts_object(schema, indent),
+            _ => "unknown".to_string(),
+        })
+        .collect();
+
+    types.join(" | ")
+}
+
+fn ts_object(schema: &Value, indent: &str) -> String {
+    let properties = match schema.get("properties").and_then(Value::as_object) {
+        Some(properties) if !properties.is_empty() => properties,
+        _ => {
+            let value = match schema.get("additionalProperties") {
+                Some(value @ Value::Object(_)) => ts_type(value, indent),
+                _ => "unknown".to_string(),
+            };
+            return format!("{{ [key: string]: {} }}", value);
+        }
+    };
+
+    let required: Vec<&str> = schema["required"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(Value::as_str)
+        .collect();
+
+    let inner = format!("{}  ", indent);
+    let mut ts = String::from("{\n");
+    for (name, property) in properties {
+        write_ts_doc(&mut ts, property, &inner);
+        let optional = if required.contains(&name.as_str()) {
+            ""
+        } else {
+            "?"
+        };
+        let _ = writeln!(
+            ts,
+            "{}{}{}: {};",
+            inner,
+            name,
+            optional,
+            ts_type(property, &inner)
+        );
+    }
+    ts.push_str(indent);
+    ts.push('}');
+
+    ts
+}
+
+pub async fn openapi_json() -> Json<utoipa::openapi::OpenApi> {
+    Json(ApiDoc::openapi())
+}
+
+/// Follows a `$ref` to a component schema, returning just its name
+fn schema_name(schema: &Value) -> Option<&str> {
+    schema
+        .get("$ref")
+        .and_then(Value::as_str)
+        .and_then(|reference| reference.rsplit('/').next())
+}
+
+fn render_schema(html: &mut String, schema: &Value) {
+    match schema_name(schema) {
+        Some(name) => {
+            let _ = write!(html, "<a href=\"#{0}\">{0}</a>", escape(name));
+        }
+        None => {
+            let pretty = serde_json::to_string_pretty(schema).unwrap_or_default();
+            let _ = write!(html, "<pre>{}</pre>", escape(&pretty));
+        }
+    }
+}
+
+fn render_content(html: &mut String, content: &Value) {
+    for (content_type, media) in content.as_object().into_iter().flatten() {
+        let _ = write!(html, "<div><code>{}</code> ", escape(content_type));
+        if let Some(schema) = media.get("schema") {
+            render_schema(html, schema);
+        }
+        html.push_str("</div>");
+    }
+}
+
+fn render_operation(html: &mut String, path: &str, method: &str, operation: &Value) {
+    let _ = write!(
+        html,
+        "<div class=\"endpoint\"><h3><span class=\"method\">{}</span> <code>{}</code> \
+         <span class=\"tag\">{}</span></h3><p>{}</p>",
+        escape(method),
+        escape(path),
+        escape(operation["tags"][0].as_str().unwrap_or("")),
+        escape(operation["summary"].as_str().unwrap_or("")),
+    );
+
+    if let Some(parameters) = operation.get("parameters").and_then(Value::as_array) {
+        html.push_str("<h4>Parameters</h4><table>");
+        for parameter in parameters {
+            let _ = write!(
+                html,
+                "<tr><td><code>{}</code></td><td>{}</td><td>{}</td></tr>",
+                escape(parameter["name"].as_str().unwrap_or("")),
+                escape(parameter["in"].as_str().unwrap_or("")),
+                escape(parameter["description"].as_str().unwrap_or("")),
+            );
+        }
+        html.push_str("</table>");
+    }
+
+    if let Some(content) = operation.pointer("/requestBody/content") {
+        html.push_str("<h4>Request body</h4>");
+        render_content(html, content);
+    }
+
+    html.push_str("<h4>Responses</h4><table>");
+    for (status, response) in operation["responses"].as_object().into_iter().flatten() {
+        let _ = write!(
+            html,
+            "<tr><td>{}</td><td>{}</td><td>",
+            escape(status),
+            escape(response["description"].as_str().unwrap_or("")),
+        );
+        if let Some(content) = response.get("content") {
+            render_content(html, content);
+        }
+        html.push_str("</td></tr>");
+    }
+    html.push_str("</table></div>");
+}
+
+/// Reference for the API rendered from the spec, without pulling in a docs viewer from a CDN
+pub async fn docs() -> Html<String> {
+    let spec = serde_json::to_value(ApiDoc::openapi()).unwrap_or_default();
+
+    let mut html = String::new();
+    let _ = write!(
+        html,
+        "<!DOCTYPE html><html><head><meta charset=\"utf-8\"><title>{0} API</title>\
+         <style>{1}</style></head><body><h1>{0} API {2}</h1><p>{3}</p>\
+         <p>Machine-readable spec: <a href=\"/api/openapi.json\">/api/openapi.json</a></p>",
+        escape(
+            spec.pointer("/info/title")
+                .and_then(Value::as_str)
+                .unwrap_or("")
+        ),
+        STYLE,
+        escape(
+            spec.pointer("/info/version")
+                .and_then(Value::as_str)
+                .unwrap_or("")
+        ),
+        escape(
+            spec.pointer("/info/description")
+                .and_then(Value::as_str)
+                .unwrap_or("")
+        ),
+    );
+
+    html.push_str("<h2>Endpoints</h2>");
+    for (path, item) in spec["paths"].as_object().into_iter().flatten() {
+        for (method, operation) in item.as_object().into_iter().flatten() {
+            render_operation(&mut html, path, method, operation);
+        }
+    }
+
+    html.push_str("<h2>Schemas</h2>");
+    for (name, schema) in spec
+        .pointer("/components/schemas")
+        .and_then(Value::as_object)
+        .into_iter()
+        .flatten()
+    {
+        let _ = write!(html, "<h3 id=\"{0}\">{0}</h3>", escape(name));
+        render_schema(&mut html, schema);
+    }
+
+    html.push_str("</body></html>");
+    Html(html)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SPEC_PATH: &str = "openapi.json";
+    const TYPESCRIPT_PATH: &str = "assets/ts/api.d.ts";
+
+    fn assert_up_to_date(path: &str, generated: String, command: &str) {
+        let full_path = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join(path);
+        let checked_in = std::fs::read_to_string(full_path).unwrap_or_default();
+
+        assert!(
+            checked_in == generated,
+            "{} is out of date; regenerate it with `cargo run -- {} > {}`",
+            path,
+            command,
+            path
+        );
+    }
+
+    #[test]
+    fn checked_in_spec_is_up_to_date() {
+        assert_up_to_date(SPEC_PATH, spec_json(), "openapi");
+    }
+
+    #[test]
+    fn checked_in_typescript_is_up_to_date() {
+        assert_up_to_date(TYPESCRIPT_PATH, typescript(), "openapi --typescript");
+    }
+}
@@ -0,0 +1,94 @@
+use anyhow::Result;
+use chrono::{DateTime, Duration};
+use chrono_tz::Tz;
+use serde::Serialize;
+
+use crate::table;
+use crate::Config;
+
+const TABLE_TIME_FORMAT: &str = "%a %Y-%m-%d %H:%M";
+
+#[derive(Serialize, Debug)]
+pub struct PreviewInstance {
+    pub expected_completion_time: DateTime<Tz>,
+    pub overdue_time: DateTime<Tz>,
+    pub expiration_time: DateTime<Tz>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct PreviewChore {
+    pub title: String,
+    pub schedule: String,
+    pub instances: Vec<PreviewInstance>,
+}
+
+/// Projects the instances the scheduler would create for each chore over the next `days` days
+pub fn preview_chores(config: &Config, days: u32) -> Result<Vec<PreviewChore>> {
+    let now = config.now();
+    let until = now + Duration::days(days as i64);
+    let overdue_duration = Duration::from_std(config.overdue_time)?;
+
+    let mut chores = Vec::new();
+    for (title, chore) in config.chores.iter() {
+        let instances = chore
+            .frequency
+            .occurrences(&now, &until)
+            .windows(2)
+            .filter(|pair| pair[0] <= until)
+            .map(|pair| PreviewInstance {
+                expected_completion_time: pair[0],
+                overdue_time: pair[0] + overdue_duration,
+                expiration_time: pair[1],
+            })
+            .collect();
+
+        chores.push(PreviewChore {
+            title: title.to_string(),
+            schedule: chore.frequency.describe().to_string(),
+            instances,
+        });
+    }
+
+    chores.sort_by(|a, b| a.title.cmp(&b.title));
+
+    Ok(chores)
+}
+
+pub fn print_json(chores: &[PreviewChore]) -> Result<()> {
+    println!("{}", serde_json::to_string_pretty(chores)?);
+
+    Ok(())
+}
+
+pub fn print_table(chores: &[PreviewChore]) {
+    let header = ["CHORE", "DUE", "OVERDUE AT", "EXPIRES"];
+
+    let mut rows = Vec::new();
+    for chore in chores.iter() {
+        let title = format!("{} ({})", chore.title, chore.schedule);
+        if chore.instances.is_empty() {
+            rows.push([title, "-".to_string(), "-".to_string(), "-".to_string()]);
+            continue;
+        }
+
+        for (i, instance) in chore.instances.iter().enumerate() {
+            rows.push([
+                match i {
+                    0 => title.clone(),
+                    _ => String::new(),
+                },
+                instance
+                    .expected_completion_time
+                    .format(TABLE_TIME_FORMAT)
+                    .to_string(),
+                instance.overdue_time.format(TABLE_TIME_FORMAT).to_string(),
+                instance
+                    .expiration_time
+                    .format(TABLE_TIME_FORMAT)
+                    .to_string(),
+            ]);
+        }
+    }
+
+    table::print_table(header, &rows);
+}
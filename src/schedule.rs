@@ -0,0 +1,982 @@
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Datelike, Duration, Months, NaiveDate, NaiveDateTime, TimeZone, Weekday};
+use chrono_tz::Tz;
+use cron::TimeUnitSpec;
+use rrule::{Frequency, NWeekday, RRule, RRuleSet, Unvalidated};
+use serde::Deserialize;
+
+const WEEKDAYS: [Weekday; 7] = [
+    Weekday::Sun,
+    Weekday::Mon,
+    Weekday::Tue,
+    Weekday::Wed,
+    Weekday::Thu,
+    Weekday::Fri,
+    Weekday::Sat,
+];
+
+/// Time of day used by phrases that don't name one
+const DEFAULT_TIME: (u8, u8) = (9, 0);
+
+#[derive(Debug, Clone)]
+enum ScheduleKind {
+    Cron(cron::Schedule),
+    /// Rules without a DTSTART, anchored by `anchor_before()` in the configured timezone
+    Rules(Vec<RRule>),
+    /// Rules with an explicit DTSTART
+    Set(RRuleSet),
+}
+
+/// A chore frequency, written as cron, an RRULE or a phrase like "every 3 days at 9am"
+#[derive(Deserialize, Debug, Clone)]
+#[serde(try_from = "String")]
+pub struct ChoreSchedule {
+    kind: ScheduleKind,
+    description: String,
+}
+
+impl ChoreSchedule {
+    /// Human-readable rendering, e.g. "Every day at 09:00 and 21:00"
+    pub fn describe(&self) -> &str {
+        &self.description
+    }
+
+    /// Every occurrence strictly after `after`, up to and including the first one past `until`
+    pub fn occurrences(&self, after: &DateTime<Tz>, until: &DateTime<Tz>) -> Vec<DateTime<Tz>> {
+        let timezone = after.timezone();
+
+        match &self.kind {
+            ScheduleKind::Cron(schedule) => take_until(schedule.after(after), until),
+            ScheduleKind::Rules(rules) => {
+                let set = RRuleSet::new(anchor_before(rules, after)).set_rrules(rules.clone());
+                take_until(
+                    set.into_iter()
+                        .map(|time| time.with_timezone(&timezone))
+                        .skip_while(|time| time <= after),
+                    until,
+                )
+            }
+            ScheduleKind::Set(set) => take_until(
+                set.into_iter()
+                    .map(|time| time.with_timezone(&timezone))
+                    .skip_while(|time| time <= after),
+                until,
+            ),
+        }
+    }
+}
+
+fn take_until(iter: impl Iterator<Item = DateTime<Tz>>, until: &DateTime<Tz>) -> Vec<DateTime<Tz>> {
+    let mut occurrences: Vec<DateTime<Tz>> = Vec::new();
+    for time in iter {
+        // Local times skipped or repeated by DST transitions can resolve to the same instant
+        if occurrences.last().is_some_and(|last| time <= *last) {
+            continue;
+        }
+
+        let past_until = time > *until;
+        occurrences.push(time);
+        if past_until {
+            break;
+        }
+    }
+
+    occurrences
+}
+
+/// Start of recurrence for rules that don't carry their own DTSTART, which also fixes the
+/// phase of intervals like "every 3 days"
+fn anchor_naive() -> NaiveDateTime {
+    NaiveDate::from_ymd_opt(2022, 1, 1)
+        .and_then(|date| date.and_hms_opt(0, 0, 0))
+        .expect("anchor date is valid")
+}
+
+fn localize(timezone: Tz, naive: &NaiveDateTime) -> DateTime<rrule::Tz> {
+    rrule::Tz::Tz(timezone)
+        .from_local_datetime(naive)
+        .earliest()
+        .unwrap_or_else(|| rrule::Tz::UTC.from_utc_datetime(naive))
+}
+
+fn anchor(timezone: Tz) -> DateTime<rrule::Tz> {
+    localize(timezone, &anchor_naive())
+}
+
+/// `anchor()` moved forward by whole periods of the rules, to at least one period before `after`.
+/// This keeps the phase of intervals the same while sparing the scheduler from walking every
+/// occurrence since the anchor on each pass, which for hourly rules grows without bound
+fn anchor_before(rules: &[RRule], after: &DateTime<Tz>) -> DateTime<rrule::Tz> {
+    let timezone = after.timezone();
+    let base = anchor_naive();
+
+    // rules parsed from one phrase share a period, but anything else keeps the original anchor
+    let (frequency, interval) = match rules.first() {
+        Some(rule)
+            if rules.iter().all(|other| {
+                other.get_freq() == rule.get_freq() && other.get_interval() == rule.get_interval()
+            }) =>
+        {
+            (rule.get_freq(), i64::from(rule.get_interval().max(1)))
+        }
+        _ => return anchor(timezone),
+    };
+
+    let elapsed = after.naive_local() - base;
+    // whole periods to skip, leaving at least one between the anchor and `after`
+    let periods = |length: i64, elapsed: i64| (elapsed / length - 1).max(0);
+
+    let shifted = match frequency {
+        Frequency::Secondly | Frequency::Minutely | Frequency::Hourly => {
+            let unit = match frequency {
+                Frequency::Secondly => 1,
+                Frequency::Minutely => 60,
+                _ => 3600,
+            };
+            let length = unit * interval;
+            base + Duration::seconds(periods(length, elapsed.num_seconds()) * length)
+        }
+        Frequency::Daily | Frequency::Weekly => {
+            let unit = match frequency {
+                Frequency::Daily => 1,
+                _ => 7,
+            };
+            let length = unit * interval;
+            base + Duration::days(periods(length, elapsed.num_days()) * length)
+        }
+        Frequency::Monthly | Frequency::Yearly => {
+            let unit = match frequency {
+                Frequency::Monthly => 1,
+                _ => 12,
+            };
+            let length = unit * interval;
+            let months = i64::from(after.year() - base.year()) * 12 + i64::from(after.month0());
+            let months = periods(length, months) * length;
+            base.checked_add_months(Months::new(months as u32))
+                .unwrap_or(base)
+        }
+    };
+
+    localize(timezone, &shifted)
+}
+
+impl FromStr for ChoreSchedule {
+    type Err = anyhow::Error;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let source = value.trim().to_string();
+        let upper = source.to_uppercase();
+
+        let kind = if upper.contains("DTSTART") {
+            let set: RRuleSet = source
+                .parse()
+                .map_err(|e| anyhow!("Invalid RRULE \"{}\": {}", source, e))?;
+            ScheduleKind::Set(set)
+        } else if upper.starts_with("RRULE:") || upper.starts_with("FREQ=") {
+            let rule = upper.trim_start_matches("RRULE:");
+            let rule: RRule<Unvalidated> = rule
+                .parse()
+                .map_err(|e| anyhow!("Invalid RRULE \"{}\": {}", source, e))?;
+            ScheduleKind::Rules(vec![validate(rule)?])
+        } else if is_cron(&source) {
+            ScheduleKind::Cron(parse_cron(&source)?)
+        } else {
+            ScheduleKind::Rules(parse_phrase(&source)?)
+        };
+
+        let description = match &kind {
+            ScheduleKind::Cron(schedule) => describe_cron(schedule, &source),
+            ScheduleKind::Rules(rules) => describe_rules(rules, &source),
+            ScheduleKind::Set(set) => describe_rules(set.get_rrule(), &source),
+        };
+
+        Ok(ChoreSchedule { kind, description })
+    }
+}
+
+impl TryFrom<String> for ChoreSchedule {
+    type Error = anyhow::Error;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+fn validate(rule: RRule<Unvalidated>) -> Result<RRule> {
+    rule.validate(anchor(Tz::UTC))
+        .map_err(|e| anyhow!("Invalid RRULE: {}", e))
+}
+
+fn is_cron_field(field: &str) -> bool {
+    field.split(|c| ",-/#".contains(c)).all(|token| {
+        token.is_empty()
+            || token == "*"
+            || token == "?"
+            || token.chars().all(|c| c.is_ascii_digit())
+            || (token.len() == 3 && token.chars().all(|c| c.is_ascii_alphabetic()))
+    })
+}
+
+fn is_cron(value: &str) -> bool {
+    let fields: Vec<&str> = value.split_whitespace().collect();
+
+    (5..=7).contains(&fields.len()) && fields.iter().all(|field| is_cron_field(field))
+}
+
+/// Parses standard 5-field cron as well as the 6- and 7-field forms with seconds and years
+fn parse_cron(value: &str) -> Result<cron::Schedule> {
+    let fields: Vec<&str> = value.split_whitespace().collect();
+
+    let expression = match fields.len() {
+        5 => format!(
+            "0 {} {} {} {} {}",
+            fields[0],
+            fields[1],
+            fields[2],
+            fields[3],
+            convert_day_of_week(fields[4])?
+        ),
+        _ => value.to_string(),
+    };
+
+    expression
+        .parse()
+        .map_err(|e| anyhow!("Invalid cron expression \"{}\": {}", value, e))
+}
+
+fn day_of_week_number(value: &str) -> Result<usize> {
+    if let Ok(number) = value.parse::<usize>() {
+        return match number {
+            0..=6 => Ok(number),
+            7 => Ok(0),
+            _ => Err(anyhow!("Invalid day of week \"{}\"", value)),
+        };
+    }
+
+    parse_weekday(value)
+        .and_then(|weekday| WEEKDAYS.iter().position(|w| *w == weekday))
+        .ok_or_else(|| anyhow!("Invalid day of week \"{}\"", value))
+}
+
+/// Standard cron numbers days of the week from Sunday = 0, while the `cron` crate numbers them
+/// from Sunday = 1, so expand the field and hand it over by name instead
+fn convert_day_of_week(field: &str) -> Result<String> {
+    if field == "*" || field == "?" {
+        return Ok(field.to_string());
+    }
+
+    let mut days = [false; 7];
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => (range, step.parse::<usize>()?),
+            None => (part, 1),
+        };
+        if step == 0 {
+            return Err(anyhow!("Invalid step in day of week \"{}\"", part));
+        }
+
+        let (start, end) = match range.split_once('-') {
+            _ if range == "*" => (0, 6),
+            Some((start, end)) => {
+                let end = match end.parse::<usize>() {
+                    Ok(7) => 7,
+                    _ => day_of_week_number(end)?,
+                };
+                let start = day_of_week_number(start)?;
+                // Sunday is 0 as the start of a range but 7 as its end, as in "5-7"
+                if end < start {
+                    return Err(anyhow!("Reversed range in day of week \"{}\"", range));
+                }
+                (start, end)
+            }
+            None if part.contains('/') => (day_of_week_number(range)?, 6),
+            None => {
+                let day = day_of_week_number(range)?;
+                (day, day)
+            }
+        };
+
+        for day in (start..=end).step_by(step) {
+            days[day % 7] = true;
+        }
+    }
+
+    let names: Vec<String> = WEEKDAYS
+        .iter()
+        .zip(days)
+        .filter(|(_, included)| *included)
+        .map(|(weekday, _)| weekday.to_string())
+        .collect();
+
+    Ok(names.join(","))
+}
+
+fn parse_weekday(value: &str) -> Option<Weekday> {
+    let value = value.to_lowercase();
+    let value = value.trim_end_matches('s');
+
+    match value {
+        "sun" | "sunday" => Some(Weekday::Sun),
+        "mon" | "monday" => Some(Weekday::Mon),
+        "tue" | "tues" | "tuesday" => Some(Weekday::Tue),
+        "wed" | "wednesday" => Some(Weekday::Wed),
+        "thu" | "thur" | "thurs" | "thursday" => Some(Weekday::Thu),
+        "fri" | "friday" => Some(Weekday::Fri),
+        "sat" | "saturday" => Some(Weekday::Sat),
+        _ => None,
+    }
+}
+
+fn weekday_name(weekday: Weekday) -> &'static str {
+    match weekday {
+        Weekday::Sun => "Sunday",
+        Weekday::Mon => "Monday",
+        Weekday::Tue => "Tuesday",
+        Weekday::Wed => "Wednesday",
+        Weekday::Thu => "Thursday",
+        Weekday::Fri => "Friday",
+        Weekday::Sat => "Saturday",
+    }
+}
+
+fn parse_ordinal(value: &str) -> Option<i16> {
+    match value {
+        "first" | "1st" => Some(1),
+        "second" | "2nd" => Some(2),
+        "third" | "3rd" => Some(3),
+        "fourth" | "4th" => Some(4),
+        "fifth" | "5th" => Some(5),
+        "last" => Some(-1),
+        _ => None,
+    }
+}
+
+fn ordinal_name(value: i16) -> String {
+    match value {
+        1 => "first".to_string(),
+        2 => "second".to_string(),
+        3 => "third".to_string(),
+        4 => "fourth".to_string(),
+        5 => "fifth".to_string(),
+        -1 => "last".to_string(),
+        _ => format!("{}th", value),
+    }
+}
+
+fn parse_day_of_month(value: &str) -> Option<i8> {
+    let value = value
+        .trim_end_matches("st")
+        .trim_end_matches("nd")
+        .trim_end_matches("rd")
+        .trim_end_matches("th");
+
+    match value.parse::<i8>() {
+        Ok(day) if (1..=31).contains(&day) => Some(day),
+        _ => None,
+    }
+}
+
+fn parse_time(value: &str) -> Result<(u8, u8)> {
+    let invalid = || anyhow!("Invalid time \"{}\"", value);
+
+    match value {
+        "noon" => return Ok((12, 0)),
+        "midnight" => return Ok((0, 0)),
+        _ => {}
+    }
+
+    let (clock, meridiem) = if let Some(clock) = value.strip_suffix("am") {
+        (clock, Some(false))
+    } else if let Some(clock) = value.strip_suffix("pm") {
+        (clock, Some(true))
+    } else {
+        (value, None)
+    };
+
+    let (hour, minute) = match clock.trim().split_once(':') {
+        Some((hour, minute)) => (hour, minute),
+        None => (clock.trim(), "0"),
+    };
+    let hour: u8 = hour.parse().map_err(|_| invalid())?;
+    let minute: u8 = minute.parse().map_err(|_| invalid())?;
+
+    let hour = match (meridiem, hour) {
+        (Some(_), 0) | (Some(_), 13..) => return Err(invalid()),
+        (Some(false), 12) => 0,
+        (Some(false), hour) => hour,
+        (Some(true), 12) => 12,
+        (Some(true), hour) => hour + 12,
+        (None, hour) => hour,
+    };
+
+    if hour > 23 || minute > 59 {
+        return Err(invalid());
+    }
+
+    Ok((hour, minute))
+}
+
+fn parse_weekdays(words: &[&str]) -> Option<Vec<NWeekday>> {
+    let weekdays: Option<Vec<NWeekday>> = words
+        .iter()
+        .filter(|word| **word != "and")
+        .map(|word| parse_weekday(word).map(NWeekday::Every))
+        .collect();
+
+    weekdays.filter(|weekdays| !weekdays.is_empty())
+}
+
+/// Turns phrases like "daily at 9:00 and 21:00", "every 3 days at 9am" or
+/// "first Saturday of the month" into equivalent RRULEs
+fn parse_phrase(value: &str) -> Result<Vec<RRule>> {
+    let unknown = || anyhow!("Unrecognized frequency \"{}\"", value);
+
+    let phrase = value.to_lowercase().replace(',', " and ");
+    let (period, times) = match phrase.split_once(" at ") {
+        Some((period, times)) => (period, Some(times)),
+        None => (phrase.as_str(), None),
+    };
+
+    let words: Vec<&str> = period.split_whitespace().collect();
+    let interval = |n: &str| match n.parse::<u16>() {
+        Ok(0) => Err(anyhow!(
+            "Frequency \"{}\" must repeat at least every 1",
+            value
+        )),
+        Ok(n) => Ok(n),
+        Err(_) => Err(unknown()),
+    };
+
+    let (rule, takes_time) = match words.as_slice() {
+        ["daily"] | ["every", "day"] => (RRule::new(Frequency::Daily), true),
+        ["every", "other", "day"] => (RRule::new(Frequency::Daily).interval(2), true),
+        ["every", n, "days"] => (RRule::new(Frequency::Daily).interval(interval(n)?), true),
+        ["hourly"] | ["every", "hour"] => (RRule::new(Frequency::Hourly), false),
+        ["every", n, "hours"] => (RRule::new(Frequency::Hourly).interval(interval(n)?), false),
+        ["weekly", "on", days @ ..] | ["every", "week", "on", days @ ..] => (
+            RRule::new(Frequency::Weekly).by_weekday(parse_weekdays(days).ok_or_else(unknown)?),
+            true,
+        ),
+        ["every", "other", "week", "on", days @ ..] => (
+            RRule::new(Frequency::Weekly)
+                .interval(2)
+                .by_weekday(parse_weekdays(days).ok_or_else(unknown)?),
+            true,
+        ),
+        ["every", n, "weeks", "on", days @ ..] => (
+            RRule::new(Frequency::Weekly)
+                .interval(interval(n)?)
+                .by_weekday(parse_weekdays(days).ok_or_else(unknown)?),
+            true,
+        ),
+        ["every", days @ ..] if parse_weekdays(days).is_some() => (
+            RRule::new(Frequency::Weekly).by_weekday(parse_weekdays(days).ok_or_else(unknown)?),
+            true,
+        ),
+        [ordinal, weekday, "of", "the" | "every", "month"] => {
+            let ordinal = parse_ordinal(ordinal).ok_or_else(unknown)?;
+            let weekday = parse_weekday(weekday).ok_or_else(unknown)?;
+            (
+                RRule::new(Frequency::Monthly).by_weekday(vec![NWeekday::Nth(ordinal, weekday)]),
+                true,
+            )
+        }
+        ["monthly", "on", "the", day] | ["every", "month", "on", "the", day] => (
+            RRule::new(Frequency::Monthly)
+                .by_month_day(vec![parse_day_of_month(day).ok_or_else(unknown)?]),
+            true,
+        ),
+        _ => return Err(unknown()),
+    };
+
+    if !takes_time {
+        if times.is_some() {
+            return Err(anyhow!(
+                "Frequency \"{}\" can't be combined with a time of day",
+                value
+            ));
+        }
+
+        return Ok(vec![validate(rule.by_minute(vec![0]).by_second(vec![0]))?]);
+    }
+
+    let times = match times {
+        Some(times) => times
+            .split(" and ")
+            .map(|time| parse_time(time.trim()))
+            .collect::<Result<Vec<_>>>()?,
+        None => vec![DEFAULT_TIME],
+    };
+
+    // A single rule takes the cross product of its hours and minutes, so group by minute
+    let mut hours_by_minute: BTreeMap<u8, Vec<u8>> = BTreeMap::new();
+    for (hour, minute) in times {
+        hours_by_minute.entry(minute).or_default().push(hour);
+    }
+
+    hours_by_minute
+        .into_iter()
+        .map(|(minute, hours)| {
+            validate(
+                rule.clone()
+                    .by_hour(hours)
+                    .by_minute(vec![minute])
+                    .by_second(vec![0]),
+            )
+        })
+        .collect()
+}
+
+fn join_words(words: &[String]) -> String {
+    match words {
+        [] => String::new(),
+        [word] => word.clone(),
+        [rest @ .., last] => format!("{} and {}", rest.join(", "), last),
+    }
+}
+
+fn describe_times(times: &[(u8, u8)]) -> String {
+    let mut times = times.to_vec();
+    times.sort_unstable();
+    times.dedup();
+
+    let times: Vec<String> = times
+        .iter()
+        .map(|(hour, minute)| format!("{:02}:{:02}", hour, minute))
+        .collect();
+
+    join_words(&times)
+}
+
+/// Splits a rule into a description of its period and the times of day it fires at
+fn describe_rule(rule: &RRule) -> Option<(String, Vec<(u8, u8)>)> {
+    if rule.get_count().is_some()
+        || rule.get_until().is_some()
+        || !rule.get_by_month().is_empty()
+        || !rule.get_by_year_day().is_empty()
+        || !rule.get_by_week_no().is_empty()
+        || !rule.get_by_set_pos().is_empty()
+        || rule.get_by_second().iter().any(|second| *second != 0)
+    {
+        return None;
+    }
+
+    let interval = rule.get_interval();
+    let every = |unit: &str| match interval {
+        1 => format!("Every {}", unit),
+        2 => format!("Every other {}", unit),
+        n => format!("Every {} {}s", n, unit),
+    };
+
+    let mut times = Vec::new();
+    for hour in rule.get_by_hour() {
+        for minute in rule.get_by_minute() {
+            times.push((*hour, *minute));
+        }
+    }
+
+    let period = match rule.get_freq() {
+        Frequency::Hourly => {
+            let minutes = rule.get_by_minute();
+            let period = match minutes {
+                [0] => every("hour"),
+                [minute] => format!("{} at {} past", every("hour"), minute),
+                _ => return None,
+            };
+            return Some((period, Vec::new()));
+        }
+        Frequency::Daily if rule.get_by_weekday().is_empty() => every("day"),
+        Frequency::Weekly | Frequency::Daily => {
+            let mut days = Vec::new();
+            for weekday in rule.get_by_weekday() {
+                match weekday {
+                    NWeekday::Every(weekday) => days.push(weekday_name(*weekday).to_string()),
+                    NWeekday::Nth(_, _) => return None,
+                }
+            }
+
+            match interval {
+                1 => format!("Every {}", join_words(&days)),
+                _ => format!("{} on {}", every("week"), join_words(&days)),
+            }
+        }
+        Frequency::Monthly => {
+            let month = match interval {
+                1 => "every month".to_string(),
+                n => format!("every {} months", n),
+            };
+
+            match (rule.get_by_weekday(), rule.get_by_month_day()) {
+                ([NWeekday::Nth(n, weekday)], []) => format!(
+                    "The {} {} of {}",
+                    ordinal_name(*n),
+                    weekday_name(*weekday),
+                    month
+                ),
+                ([], days) if !days.is_empty() => {
+                    let days: Vec<String> = days.iter().map(|day| day.to_string()).collect();
+                    format!("Day {} of {}", join_words(&days), month)
+                }
+                _ => return None,
+            }
+        }
+        _ => return None,
+    };
+
+    Some((period, times))
+}
+
+fn describe_rules(rules: &[RRule], source: &str) -> String {
+    // Merge rules that only differ by time of day, as produced for "daily at 9:00 and 21:30"
+    let mut periods: Vec<(String, Vec<(u8, u8)>)> = Vec::new();
+    for rule in rules {
+        let (period, times) = match describe_rule(rule) {
+            Some(description) => description,
+            None => return source.to_string(),
+        };
+
+        match periods.iter_mut().find(|(existing, _)| *existing == period) {
+            Some((_, existing_times)) => existing_times.extend(times),
+            None => periods.push((period, times)),
+        }
+    }
+
+    let descriptions: Vec<String> = periods
+        .into_iter()
+        .map(|(period, times)| match times.is_empty() {
+            true => period,
+            false => format!("{} at {}", period, describe_times(&times)),
+        })
+        .collect();
+
+    descriptions.join("; ")
+}
+
+fn describe_cron(schedule: &cron::Schedule, source: &str) -> String {
+    let fallback = || format!("Cron schedule \"{}\"", source);
+
+    if !schedule.months().is_all() || !schedule.years().is_all() {
+        return fallback();
+    }
+    if schedule.seconds().iter().any(|second| second != 0)
+        || schedule.hours().count() * schedule.minutes().count() > 6
+    {
+        return fallback();
+    }
+
+    let mut times = Vec::new();
+    for hour in schedule.hours().iter() {
+        for minute in schedule.minutes().iter() {
+            times.push((hour as u8, minute as u8));
+        }
+    }
+
+    let period = match (
+        schedule.days_of_month().is_all(),
+        schedule.days_of_week().is_all(),
+    ) {
+        (true, true) => "Every day".to_string(),
+        (true, false) => {
+            // The cron crate numbers days of the week from Sunday = 1
+            let days: Vec<String> = schedule
+                .days_of_week()
+                .iter()
+                .map(|day| weekday_name(WEEKDAYS[(day as usize - 1) % 7]).to_string())
+                .collect();
+            format!("Every {}", join_words(&days))
+        }
+        (false, true) => {
+            let fields: Vec<&str> = source.split_whitespace().collect();
+            let day_field = match fields.len() {
+                5 => fields[2],
+                _ => fields[3],
+            };
+
+            match day_field.strip_prefix("*/") {
+                Some(step) => format!("Every {} days of the month", step),
+                None => {
+                    let days: Vec<String> = schedule
+                        .days_of_month()
+                        .iter()
+                        .map(|day| day.to_string())
+                        .collect();
+                    format!("Day {} of every month", join_words(&days))
+                }
+            }
+        }
+        (false, false) => return fallback(),
+    };
+
+    format!("{} at {}", period, describe_times(&times))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn time(
+        zone: Tz,
+        (year, month, day): (i32, u32, u32),
+        (hour, minute): (u32, u32),
+    ) -> DateTime<Tz> {
+        zone.with_ymd_and_hms(year, month, day, hour, minute, 0)
+            .unwrap()
+    }
+
+    /// Sunday 2026-10-18 12:00 in Denver
+    fn after() -> DateTime<Tz> {
+        time(Tz::America__Denver, (2026, 10, 18), (12, 0))
+    }
+
+    fn first_occurrences(frequency: &str, count: usize) -> Vec<String> {
+        let schedule: ChoreSchedule = frequency.parse().unwrap();
+        schedule
+            .occurrences(&after(), &(after() + Duration::days(40)))
+            .iter()
+            .take(count)
+            .map(|time| time.format("%a %m-%d %H:%M").to_string())
+            .collect()
+    }
+
+    #[test]
+    fn readme_examples() {
+        let cases: &[(&str, &str, &[&str])] = &[
+            (
+                "0 9,21 * * *",
+                "Every day at 09:00 and 21:00",
+                &["Sun 10-18 21:00", "Mon 10-19 09:00", "Mon 10-19 21:00"],
+            ),
+            (
+                "0 0 9,21 * * * *",
+                "Every day at 09:00 and 21:00",
+                &["Sun 10-18 21:00", "Mon 10-19 09:00", "Mon 10-19 21:00"],
+            ),
+            (
+                "FREQ=WEEKLY;BYDAY=MO,TH;BYHOUR=18;BYMINUTE=0",
+                "Every Monday and Thursday at 18:00",
+                &["Mon 10-19 18:00", "Thu 10-22 18:00", "Mon 10-26 18:00"],
+            ),
+            (
+                "daily at 9:00 and 21:00",
+                "Every day at 09:00 and 21:00",
+                &["Sun 10-18 21:00", "Mon 10-19 09:00", "Mon 10-19 21:00"],
+            ),
+            (
+                "every 3 days at 9am",
+                "Every 3 days at 09:00",
+                &["Mon 10-19 09:00", "Thu 10-22 09:00", "Sun 10-25 09:00"],
+            ),
+            (
+                "every monday and thursday at 6:30pm",
+                "Every Monday and Thursday at 18:30",
+                &["Mon 10-19 18:30", "Thu 10-22 18:30", "Mon 10-26 18:30"],
+            ),
+            (
+                "first Saturday of the month",
+                "The first Saturday of every month at 09:00",
+                &["Sat 11-07 09:00", "Sat 12-05 09:00"],
+            ),
+            (
+                "every tuesday at 6pm",
+                "Every Tuesday at 18:00",
+                &["Tue 10-20 18:00", "Tue 10-27 18:00", "Tue 11-03 18:00"],
+            ),
+        ];
+
+        for (frequency, description, occurrences) in cases {
+            let schedule: ChoreSchedule = frequency.parse().unwrap();
+            assert_eq!(schedule.describe(), *description, "{}", frequency);
+            assert_eq!(
+                first_occurrences(frequency, occurrences.len()),
+                *occurrences,
+                "{}",
+                frequency
+            );
+        }
+    }
+
+    #[test]
+    fn day_of_week_conversion() {
+        let cases = [
+            ("*", "*"),
+            ("0", "Sun"),
+            ("7", "Sun"),
+            ("sun", "Sun"),
+            ("Thursday", "Thu"),
+            ("1-5", "Mon,Tue,Wed,Thu,Fri"),
+            ("mon-fri", "Mon,Tue,Wed,Thu,Fri"),
+            ("5-7", "Sun,Fri,Sat"),
+            ("0,6", "Sun,Sat"),
+            ("*/2", "Sun,Tue,Thu,Sat"),
+            ("1/3", "Mon,Thu"),
+            ("1-5/2", "Mon,Wed,Fri"),
+        ];
+
+        for (field, expected) in cases {
+            assert_eq!(convert_day_of_week(field).unwrap(), expected, "{}", field);
+        }
+
+        for field in ["5-1", "sat-mon", "8", "funday", "*/0", "1/x"] {
+            assert!(convert_day_of_week(field).is_err(), "{}", field);
+        }
+    }
+
+    #[test]
+    fn parses_times() {
+        let cases = [
+            ("9", (9, 0)),
+            ("9:30", (9, 30)),
+            ("21:05", (21, 5)),
+            ("9am", (9, 0)),
+            ("9 pm", (21, 0)),
+            ("6:30pm", (18, 30)),
+            ("12am", (0, 0)),
+            ("12pm", (12, 0)),
+            ("noon", (12, 0)),
+            ("midnight", (0, 0)),
+        ];
+        for (value, expected) in cases {
+            assert_eq!(parse_time(value).unwrap(), expected, "{}", value);
+        }
+
+        for value in ["24:00", "9:60", "13pm", "0am", "nine", "9:xx", ""] {
+            assert!(parse_time(value).is_err(), "{}", value);
+        }
+    }
+
+    #[test]
+    fn descriptions() {
+        let cases = [
+            ("0 9 * * 0", "Every Sunday at 09:00"),
+            ("0 9 * * 7", "Every Sunday at 09:00"),
+            (
+                "0 9 * * mon-fri",
+                "Every Monday, Tuesday, Wednesday, Thursday and Friday at 09:00",
+            ),
+            ("0 9 1,15 * *", "Day 1 and 15 of every month at 09:00"),
+            ("0 0 9 */3 * * *", "Every 3 days of the month at 09:00"),
+            ("0 9 1 1 *", "Cron schedule \"0 9 1 1 *\""),
+            ("*/5 * * * *", "Cron schedule \"*/5 * * * *\""),
+            ("hourly", "Every hour"),
+            ("every 5 hours", "Every 5 hours"),
+            ("FREQ=HOURLY;BYMINUTE=15", "Every hour at 15 past"),
+            ("every other day", "Every other day at 09:00"),
+            (
+                "every other week on monday",
+                "Every other week on Monday at 09:00",
+            ),
+            (
+                "monthly on the 15th at noon",
+                "Day 15 of every month at 12:00",
+            ),
+            (
+                "last friday of the month at 5pm",
+                "The last Friday of every month at 17:00",
+            ),
+            ("daily at 9:00 and 21:30", "Every day at 09:00 and 21:30"),
+            ("FREQ=DAILY;COUNT=3", "FREQ=DAILY;COUNT=3"),
+        ];
+
+        for (frequency, expected) in cases {
+            let schedule: ChoreSchedule = frequency.parse().unwrap();
+            assert_eq!(schedule.describe(), expected, "{}", frequency);
+        }
+    }
+
+    #[test]
+    fn errors() {
+        let cases = [
+            ("0 9 * * 5-1", "Reversed range in day of week \"5-1\""),
+            ("0 9 * * 8", "Invalid day of week \"8\""),
+            ("0 9 * * */0", "Invalid step in day of week \"*/0\""),
+            ("61 9 * * *", "Invalid cron expression \"61 9 * * *\""),
+            ("FREQ=NOPE", "Invalid RRULE \"FREQ=NOPE\""),
+            (
+                "every blue moon",
+                "Unrecognized frequency \"every blue moon\"",
+            ),
+            ("every 0 days", "must repeat at least every 1"),
+            ("daily at 25:00", "Invalid time \"25:00\""),
+            ("hourly at 9am", "can't be combined with a time of day"),
+            ("the 32nd of every month", "Unrecognized frequency"),
+            ("", "Unrecognized frequency"),
+        ];
+
+        for (frequency, expected) in cases {
+            let error = frequency.parse::<ChoreSchedule>().unwrap_err().to_string();
+            assert!(error.contains(expected), "{}: {}", frequency, error);
+        }
+    }
+
+    /// Every occurrence after `after`, walked from the original 2022 anchor
+    fn occurrences_from_fixed_anchor(
+        rules: &[RRule],
+        after: &DateTime<Tz>,
+        until: &DateTime<Tz>,
+    ) -> Vec<DateTime<Tz>> {
+        let timezone = after.timezone();
+        let set = RRuleSet::new(anchor(timezone)).set_rrules(rules.to_vec());
+        take_until(
+            set.into_iter()
+                .map(|time| time.with_timezone(&timezone))
+                .skip_while(|time| time <= after),
+            until,
+        )
+    }
+
+    #[test]
+    fn moving_the_anchor_keeps_the_phase() {
+        let frequencies = [
+            "every 3 days at 9am",
+            "every 5 hours",
+            "every other week on monday and friday",
+            "every 3 weeks on sunday at 6pm",
+            "FREQ=MINUTELY;INTERVAL=7",
+            "FREQ=MONTHLY;INTERVAL=5;BYMONTHDAY=31",
+            "FREQ=YEARLY;INTERVAL=3;BYMONTH=2;BYMONTHDAY=29",
+            "daily at 9:00 and 21:30",
+        ];
+        let afters = [
+            time(Tz::America__Denver, (2022, 1, 1), (0, 0)),
+            time(Tz::America__Denver, (2023, 3, 12), (1, 30)),
+            time(Tz::America__Denver, (2026, 10, 18), (12, 0)),
+            time(Tz::Europe__London, (2031, 10, 26), (0, 45)),
+        ];
+
+        for frequency in frequencies {
+            let schedule: ChoreSchedule = frequency.parse().unwrap();
+            let rules = match &schedule.kind {
+                ScheduleKind::Rules(rules) => rules,
+                _ => panic!("{} isn't anchored", frequency),
+            };
+
+            for after in afters.iter() {
+                let until = *after + Duration::days(400);
+                assert_eq!(
+                    schedule.occurrences(after, &until),
+                    occurrences_from_fixed_anchor(rules, after, &until),
+                    "{} after {}",
+                    frequency,
+                    after
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn anchor_stays_close_to_after() {
+        let schedule: ChoreSchedule = "FREQ=MINUTELY".parse().unwrap();
+        let rules = match &schedule.kind {
+            ScheduleKind::Rules(rules) => rules,
+            _ => unreachable!(),
+        };
+
+        let after = time(Tz::UTC, (2040, 6, 1), (12, 0));
+        let anchor = anchor_before(rules, &after);
+        assert!(anchor.with_timezone(&Tz::UTC) < after);
+        assert!(after - anchor.with_timezone(&Tz::UTC) <= Duration::minutes(2));
+    }
+}
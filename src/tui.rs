@@ -0,0 +1,490 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration as StdDuration, Instant};
+
+use anyhow::{anyhow, Result};
+use chores::api::{
+    ApiChore, ApiStatus, CompleteChoreParams, CompleteChoreResponse, DismissFlashParams,
+    DismissFlashResponse, Flash, FlashPriority, GetFlashResponse, GetMetarsResponse,
+    ListChoresParams,
+};
+use chores::weather::StationMetar;
+use clap::Args;
+use crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, List, ListItem, ListState, Paragraph};
+use ratatui::{DefaultTerminal, Frame};
+use sqlx::SqlitePool;
+
+use crate::client::{self, Client};
+use crate::events::EventBus;
+use crate::observations::cached_metars;
+use crate::{complete_chore_impl, dismiss_flash_impl, get_flashes_impl, list_chores_impl, Config};
+
+#[derive(Args, Debug)]
+pub struct TuiArgs {
+    /// Read and write the local database from DATABASE_URL instead of going through the HTTP API
+    #[arg(long)]
+    local: bool,
+    /// Client config file with the server URL and token
+    #[arg(long, conflicts_with = "local")]
+    client_config: Option<String>,
+    /// Server URL, overriding the client config
+    #[arg(long, conflicts_with = "local")]
+    server: Option<String>,
+    /// Household member to dismiss messages as, overriding the client config
+    #[arg(long)]
+    member: Option<String>,
+    /// Seconds between refreshes
+    #[arg(long, default_value_t = 10)]
+    refresh: u64,
+}
+
+/// Where the dashboard reads from and writes to
+enum Backend {
+    Http(Client),
+    Local {
+        pool: Arc<SqlitePool>,
+        config: Arc<Config>,
+        /// Only this process would see these, so nothing listens to them
+        events: Arc<EventBus>,
+        member: Option<String>,
+    },
+}
+
+impl Backend {
+    async fn chores(&self) -> Result<Vec<ApiChore>> {
+        match self {
+            Backend::Http(client) => client::list_chores(client, None).await,
+            Backend::Local { pool, config, .. } => {
+                let params = ListChoresParams {
+                    lookback_days: None,
+                };
+                list_chores_impl(params, pool.clone(), config.clone()).await
+            }
+        }
+    }
+
+    async fn flashes(&self) -> Result<Vec<Flash>> {
+        match self {
+            Backend::Http(client) => {
+                let response: GetFlashResponse = client.get("/api/flashes", &[]).await?;
+                match response.success {
+                    true => Ok(response.flashes),
+                    false => Err(anyhow!(response.error.unwrap_or_default())),
+                }
+            }
+            Backend::Local { pool, .. } => get_flashes_impl(pool.clone()).await,
+        }
+    }
+
+    async fn metars(&self) -> Result<HashMap<String, StationMetar>> {
+        match self {
+            Backend::Http(client) => {
+                let response: GetMetarsResponse = client.get("/api/metars", &[]).await?;
+                Ok(response.stations)
+            }
+            Backend::Local { pool, config, .. } => cached_metars(pool, config).await,
+        }
+    }
+
+    async fn complete(&self, chore: &ApiChore) -> Result<()> {
+        let params = CompleteChoreParams {
+            id: Some(chore.id),
+            ..Default::default()
+        };
+
+        match self {
+            Backend::Http(client) => {
+                let response: CompleteChoreResponse =
+                    client.post("/api/chores/complete", &params).await?;
+                match response.success {
+                    true => Ok(()),
+                    false => Err(anyhow!(response.error.unwrap_or_default())),
+                }
+            }
+            Backend::Local { pool, events, .. } => {
+                complete_chore_impl(params, pool.clone(), events).await?;
+                Ok(())
+            }
+        }
+    }
+
+    async fn dismiss(&self, flash: &Flash) -> Result<()> {
+        match self {
+            Backend::Http(client) => {
+                let params = DismissFlashParams {
+                    id: flash.id,
+                    member: client.member().map(str::to_string),
+                };
+                let response: DismissFlashResponse =
+                    client.post("/api/flashes/dismiss", &params).await?;
+                match response.success {
+                    true => Ok(()),
+                    false => Err(anyhow!(response.error.unwrap_or_default())),
+                }
+            }
+            Backend::Local {
+                pool,
+                events,
+                member,
+                ..
+            } => {
+                let params = DismissFlashParams {
+                    id: flash.id,
+                    member: member.clone(),
+                };
+                dismiss_flash_impl(params, pool.clone(), events).await
+            }
+        }
+    }
+}
+
+#[derive(PartialEq, Eq, Clone, Copy)]
+enum Focus {
+    Chores,
+    Flashes,
+}
+
+struct Dashboard {
+    chores: Vec<ApiChore>,
+    flashes: Vec<Flash>,
+    metars: Vec<(String, StationMetar)>,
+    focus: Focus,
+    chore_state: ListState,
+    flash_state: ListState,
+    status: String,
+}
+
+fn status_style(status: &ApiStatus) -> Style {
+    match status {
+        ApiStatus::Overdue => Style::default().fg(Color::Yellow),
+        ApiStatus::Assigned => Style::default().fg(Color::Cyan),
+        ApiStatus::Upcoming => Style::default().fg(Color::DarkGray),
+        ApiStatus::Missed => Style::default()
+            .fg(Color::Red)
+            .add_modifier(Modifier::CROSSED_OUT),
+        ApiStatus::Cancelled => Style::default()
+            .fg(Color::DarkGray)
+            .add_modifier(Modifier::CROSSED_OUT),
+        ApiStatus::Completed => Style::default()
+            .fg(Color::Green)
+            .add_modifier(Modifier::CROSSED_OUT),
+    }
+}
+
+fn select_within(state: &mut ListState, len: usize) {
+    match (state.selected(), len) {
+        (_, 0) => state.select(None),
+        (None, _) => state.select(Some(0)),
+        (Some(selected), len) if selected >= len => state.select(Some(len - 1)),
+        _ => {}
+    }
+}
+
+impl Dashboard {
+    fn new() -> Self {
+        Dashboard {
+            chores: Vec::new(),
+            flashes: Vec::new(),
+            metars: Vec::new(),
+            focus: Focus::Chores,
+            chore_state: ListState::default(),
+            flash_state: ListState::default(),
+            status: String::new(),
+        }
+    }
+
+    async fn refresh(&mut self, backend: &Backend) {
+        let mut errors = Vec::new();
+
+        match backend.chores().await {
+            Ok(mut chores) => {
+                chores
+                    .sort_by_key(|chore| (chore.status.priority(), chore.expected_completion_time));
+                self.chores = chores;
+            }
+            Err(e) => errors.push(format!("chores: {}", e)),
+        }
+
+        match backend.flashes().await {
+            Ok(flashes) => self.flashes = flashes,
+            Err(e) => errors.push(format!("messages: {}", e)),
+        }
+
+        match backend.metars().await {
+            Ok(metars) => {
+                let mut metars: Vec<_> = metars.into_iter().collect();
+                metars.sort_by(|a, b| a.0.cmp(&b.0));
+                self.metars = metars;
+            }
+            Err(e) => errors.push(format!("weather: {}", e)),
+        }
+
+        select_within(&mut self.chore_state, self.chores.len());
+        select_within(&mut self.flash_state, self.flashes.len());
+
+        self.status = match errors.is_empty() {
+            true => format!("Updated {}", chrono::Local::now().format("%H:%M:%S")),
+            false => format!("Failed to refresh {}", errors.join("; ")),
+        };
+    }
+
+    fn move_selection(&mut self, offset: isize) {
+        let (state, len) = match self.focus {
+            Focus::Chores => (&mut self.chore_state, self.chores.len()),
+            Focus::Flashes => (&mut self.flash_state, self.flashes.len()),
+        };
+
+        if len == 0 {
+            return;
+        }
+
+        let selected = state.selected().unwrap_or(0) as isize + offset;
+        state.select(Some(selected.clamp(0, len as isize - 1) as usize));
+    }
+
+    /// Completes the selected chore or dismisses the selected message
+    async fn act(&mut self, backend: &Backend) {
+        let result = match self.focus {
+            Focus::Chores => match self.chore_state.selected().and_then(|i| self.chores.get(i)) {
+                Some(chore) if matches!(chore.status, ApiStatus::Assigned | ApiStatus::Overdue) => {
+                    backend.complete(chore).await
+                }
+                Some(chore) => Err(anyhow!("\"{}\" can't be completed yet", chore.display_name)),
+                None => return,
+            },
+            Focus::Flashes => match self
+                .flash_state
+                .selected()
+                .and_then(|i| self.flashes.get(i))
+            {
+                Some(flash) => backend.dismiss(flash).await,
+                None => return,
+            },
+        };
+
+        match result {
+            Ok(()) => self.refresh(backend).await,
+            Err(e) => self.status = format!("Error: {}", e),
+        }
+    }
+
+    fn render(&mut self, frame: &mut Frame) {
+        let flash_height = (self.flashes.len().clamp(1, 6) + 2) as u16;
+        let metar_height = (self.metars.len().clamp(1, 4) + 2) as u16;
+
+        let [header, flashes, metars, chores, footer] = Layout::vertical([
+            Constraint::Length(1),
+            Constraint::Length(flash_height),
+            Constraint::Length(metar_height),
+            Constraint::Min(3),
+            Constraint::Length(1),
+        ])
+        .areas(frame.area());
+
+        frame.render_widget(
+            Paragraph::new(Line::from(vec![
+                Span::styled("Chores List", Style::default().add_modifier(Modifier::BOLD)),
+                Span::raw(
+                    "  q quit · tab switch · ↑/↓ select · enter complete/dismiss · r refresh",
+                ),
+            ])),
+            header,
+        );
+
+        let focused_block = |title: &str, focused: bool| {
+            let block = Block::default()
+                .borders(Borders::ALL)
+                .title(title.to_string());
+            match focused {
+                true => block.border_style(Style::default().fg(Color::White)),
+                false => block.border_style(Style::default().fg(Color::DarkGray)),
+            }
+        };
+        let highlight = Style::default().add_modifier(Modifier::REVERSED);
+
+        let flash_items: Vec<ListItem> = self
+            .flashes
+            .iter()
+            .map(|flash| {
+                let contents_style = match flash.priority {
+                    FlashPriority::Urgent => {
+                        Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)
+                    }
+                    FlashPriority::Warning => Style::default().fg(Color::Yellow),
+                    FlashPriority::Info => Style::default(),
+                };
+
+                let mut spans = vec![
+                    Span::styled(
+                        client::format_timestamp(flash.created_at),
+                        Style::default().fg(Color::DarkGray),
+                    ),
+                    Span::raw("  "),
+                    Span::styled(flash.contents.clone(), contents_style),
+                ];
+                if let Some(author) = &flash.author {
+                    spans.push(Span::styled(
+                        format!("  - {}", author),
+                        Style::default().fg(Color::DarkGray),
+                    ));
+                }
+                if let Some(recipient) = &flash.recipient {
+                    spans.push(Span::styled(
+                        format!("  (for {})", recipient),
+                        Style::default().fg(Color::DarkGray),
+                    ));
+                }
+
+                ListItem::new(Line::from(spans))
+            })
+            .collect();
+        frame.render_stateful_widget(
+            List::new(flash_items)
+                .block(focused_block("Messages", self.focus == Focus::Flashes))
+                .highlight_style(highlight),
+            flashes,
+            &mut self.flash_state,
+        );
+
+        let metar_lines: Vec<Line> = self
+            .metars
+            .iter()
+            .map(|(station, metar)| {
+                let mut spans = vec![Span::styled(
+                    station.clone(),
+                    Style::default().add_modifier(Modifier::BOLD),
+                )];
+                if !metar.summary.is_empty() {
+                    spans.push(Span::raw(format!("  {}", metar.summary)));
+                } else if let Some(temperature) = metar.temperature {
+                    spans.push(Span::raw(format!("  {}°C", temperature)));
+                }
+                if let Some(category) = metar.flight_category {
+                    spans.push(Span::raw(format!("  {}", category.name())));
+                }
+                if let Some(pressure) = metar.pressure {
+                    spans.push(Span::raw(format!("  {} hPa", pressure)));
+                }
+                spans.push(Span::styled(
+                    format!("  {}", metar.metar),
+                    Style::default().fg(Color::DarkGray),
+                ));
+                Line::from(spans)
+            })
+            .collect();
+        frame.render_widget(
+            Paragraph::new(metar_lines).block(focused_block("Weather", false)),
+            metars,
+        );
+
+        let chore_items: Vec<ListItem> = self
+            .chores
+            .iter()
+            .map(|chore| {
+                ListItem::new(Line::from(vec![
+                    Span::styled(
+                        format!("{:<10}", client::status_name(&chore.status)),
+                        status_style(&chore.status),
+                    ),
+                    Span::styled(chore.display_name.clone(), status_style(&chore.status)),
+                    Span::styled(
+                        format!("  due {}", client::format_due(chore)),
+                        Style::default().fg(Color::DarkGray),
+                    ),
+                ]))
+            })
+            .collect();
+        frame.render_stateful_widget(
+            List::new(chore_items)
+                .block(focused_block("Chores", self.focus == Focus::Chores))
+                .highlight_style(highlight),
+            chores,
+            &mut self.chore_state,
+        );
+
+        frame.render_widget(
+            Paragraph::new(self.status.clone()).style(Style::default().fg(Color::DarkGray)),
+            footer,
+        );
+    }
+}
+
+async fn run_dashboard(
+    terminal: &mut DefaultTerminal,
+    backend: Backend,
+    refresh_interval: StdDuration,
+) -> Result<()> {
+    let mut dashboard = Dashboard::new();
+    dashboard.refresh(&backend).await;
+    let mut last_refresh = Instant::now();
+
+    loop {
+        terminal.draw(|frame| dashboard.render(frame))?;
+
+        if last_refresh.elapsed() >= refresh_interval {
+            dashboard.refresh(&backend).await;
+            last_refresh = Instant::now();
+        }
+
+        if !event::poll(StdDuration::from_millis(250))? {
+            continue;
+        }
+
+        let key = match event::read()? {
+            Event::Key(key) if key.kind == KeyEventKind::Press => key,
+            _ => continue,
+        };
+
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
+            KeyCode::Tab | KeyCode::BackTab => {
+                dashboard.focus = match dashboard.focus {
+                    Focus::Chores => Focus::Flashes,
+                    Focus::Flashes => Focus::Chores,
+                }
+            }
+            KeyCode::Up | KeyCode::Char('k') => dashboard.move_selection(-1),
+            KeyCode::Down | KeyCode::Char('j') => dashboard.move_selection(1),
+            KeyCode::Enter | KeyCode::Char('c') | KeyCode::Char('d') => {
+                dashboard.act(&backend).await;
+                last_refresh = Instant::now();
+            }
+            KeyCode::Char('r') => {
+                dashboard.refresh(&backend).await;
+                last_refresh = Instant::now();
+            }
+            _ => {}
+        }
+    }
+}
+
+pub async fn run(args: TuiArgs, config_path: &str) -> Result<()> {
+    let backend = match args.local {
+        true => {
+            let config = Config::from_path(config_path)?;
+            let pool = Arc::new(SqlitePool::connect(&std::env::var("DATABASE_URL")?).await?);
+            sqlx::migrate!().run(&*pool).await?;
+            Backend::Local {
+                pool,
+                config,
+                events: EventBus::new(),
+                member: args.member,
+            }
+        }
+        false => Backend::Http(Client::connect(
+            args.client_config.as_deref(),
+            args.server,
+            args.member,
+        )?),
+    };
+
+    let mut terminal = ratatui::init();
+    let result = run_dashboard(&mut terminal, backend, StdDuration::from_secs(args.refresh)).await;
+    ratatui::restore();
+
+    result
+}
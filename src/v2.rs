@@ -0,0 +1,359 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use axum::async_trait;
+use axum::body::HttpBody;
+use axum::extract::rejection::{PathRejection, QueryRejection};
+use axum::extract::{Form, FromRequest, Path, Query, RequestParts};
+use axum::http::{header, StatusCode, Uri};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post, put};
+use axum::{BoxError, Extension, Json, Router};
+use chores::api::{
+    AddFlashParams, ApiChore, ApiData, ApiErrorResponse, ArchivedChore, ChoreInstance,
+    CompleteChoreParams, DismissFlashParams, Flash, FlashHistoryPage, FlashHistoryParams, FlashId,
+    ListChoresParams, UpdateFlashParams,
+};
+use chores::weather::StationMetar;
+use serde::de::DeserializeOwned;
+use sqlx::SqlitePool;
+use tokio::sync::RwLock;
+
+use crate::catch_up::CatchUpReport;
+use crate::error::ApiError;
+use crate::events::EventBus;
+use crate::observations::cached_metars;
+use crate::{
+    add_flash_impl, complete_chore_impl, delete_flash_impl, dismiss_flash_impl, flash_history_impl,
+    get_flashes_impl, list_archived_chores_impl, list_chores_impl, update_flash_impl, Config,
+};
+
+type ApiResult<T> = Result<Json<ApiData<T>>, ApiError>;
+
+fn data<T>(data: T) -> Json<ApiData<T>> {
+    Json(ApiData { data })
+}
+
+/// Describes a rejected request along with its underlying causes, which axum leaves out
+fn rejection_message(error: &dyn std::error::Error) -> String {
+    let mut message = error.to_string();
+    let mut source = error.source();
+    while let Some(cause) = source {
+        let cause_message = cause.to_string();
+        // wrapping errors often repeat their cause's message
+        if !message.contains(&cause_message) {
+            message.push_str(&format!(": {}", cause_message));
+        }
+        source = cause.source();
+    }
+
+    message
+}
+
+/// Request body that may be either JSON or a URL-encoded form, depending on its content type
+pub struct FormOrJson<T>(pub T);
+
+#[async_trait]
+impl<T, B> FromRequest<B> for FormOrJson<T>
+where
+    T: DeserializeOwned,
+    B: HttpBody + Send,
+    B::Data: Send,
+    B::Error: Into<BoxError>,
+{
+    type Rejection = ApiError;
+
+    async fn from_request(req: &mut RequestParts<B>) -> Result<Self, Self::Rejection> {
+        let is_json = req
+            .headers()
+            .get(header::CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .is_some_and(|value| value.starts_with("application/json"));
+
+        match is_json {
+            true => match Json::<T>::from_request(req).await {
+                Ok(Json(value)) => Ok(FormOrJson(value)),
+                Err(e) => Err(ApiError::bad_request(rejection_message(&e))),
+            },
+            false => match Form::<T>::from_request(req).await {
+                Ok(Form(value)) => Ok(FormOrJson(value)),
+                Err(e) => Err(ApiError::bad_request(rejection_message(&e))),
+            },
+        }
+    }
+}
+
+/// List chore instances from the lookback window onwards
+#[utoipa::path(
+    get,
+    path = "/api/v2/chores",
+    operation_id = "v2_list_chores",
+    tag = "v2",
+    params(ListChoresParams),
+    responses(
+        (status = 200, body = ApiData<Vec<ApiChore>>),
+        (status = 400, body = ApiErrorResponse),
+        (status = 500, body = ApiErrorResponse)
+    )
+)]
+async fn list_chores(
+    query: Result<Query<ListChoresParams>, QueryRejection>,
+    Extension(pool): Extension<Arc<SqlitePool>>,
+    Extension(config): Extension<Arc<Config>>,
+) -> ApiResult<Vec<ApiChore>> {
+    let Query(params) = query.map_err(|e| ApiError::bad_request(rejection_message(&e)))?;
+
+    Ok(data(list_chores_impl(params, pool, config).await?))
+}
+
+/// List chores that were removed from the config, along with their history
+#[utoipa::path(
+    get,
+    path = "/api/v2/chores/archived",
+    operation_id = "v2_list_archived_chores",
+    tag = "v2",
+    responses(
+        (status = 200, body = ApiData<Vec<ArchivedChore>>),
+        (status = 500, body = ApiErrorResponse)
+    )
+)]
+async fn list_archived_chores(
+    Extension(pool): Extension<Arc<SqlitePool>>,
+    Extension(config): Extension<Arc<Config>>,
+) -> ApiResult<Vec<ArchivedChore>> {
+    Ok(data(list_archived_chores_impl(pool, config).await?))
+}
+
+/// Mark a chore instance as completed
+#[utoipa::path(
+    post,
+    path = "/api/v2/chores/complete",
+    operation_id = "v2_complete_chore",
+    tag = "v2",
+    request_body(content(
+        (CompleteChoreParams = "application/json"),
+        (CompleteChoreParams = "application/x-www-form-urlencoded")
+    )),
+    responses(
+        (status = 200, body = ApiData<ChoreInstance>),
+        (status = 400, body = ApiErrorResponse),
+        (status = 404, body = ApiErrorResponse),
+        (status = 409, body = ApiErrorResponse),
+        (status = 500, body = ApiErrorResponse)
+    )
+)]
+async fn complete_chore(
+    Extension(pool): Extension<Arc<SqlitePool>>,
+    Extension(events): Extension<Arc<EventBus>>,
+    FormOrJson(params): FormOrJson<CompleteChoreParams>,
+) -> ApiResult<ChoreInstance> {
+    Ok(data(complete_chore_impl(params, pool, &events).await?))
+}
+
+/// List messages that haven't been dismissed
+#[utoipa::path(
+    get,
+    path = "/api/v2/flashes",
+    operation_id = "v2_list_flashes",
+    tag = "v2",
+    responses(
+        (status = 200, body = ApiData<Vec<Flash>>),
+        (status = 500, body = ApiErrorResponse)
+    )
+)]
+async fn list_flashes(Extension(pool): Extension<Arc<SqlitePool>>) -> ApiResult<Vec<Flash>> {
+    Ok(data(get_flashes_impl(pool).await?))
+}
+
+/// Add a message to the dashboard
+#[utoipa::path(
+    post,
+    path = "/api/v2/flashes",
+    operation_id = "v2_add_flash",
+    tag = "v2",
+    request_body(content(
+        (AddFlashParams = "application/json"),
+        (AddFlashParams = "application/x-www-form-urlencoded")
+    )),
+    responses(
+        (status = 201, body = ApiData<FlashId>),
+        (status = 400, body = ApiErrorResponse),
+        (status = 500, body = ApiErrorResponse)
+    )
+)]
+async fn add_flash(
+    Extension(pool): Extension<Arc<SqlitePool>>,
+    Extension(config): Extension<Arc<Config>>,
+    Extension(events): Extension<Arc<EventBus>>,
+    FormOrJson(params): FormOrJson<AddFlashParams>,
+) -> Result<(StatusCode, Json<ApiData<FlashId>>), ApiError> {
+    let id = add_flash_impl(params, pool, &config, &events).await?;
+
+    Ok((StatusCode::CREATED, data(FlashId { id })))
+}
+
+/// List every message, including dismissed ones, newest first
+#[utoipa::path(
+    get,
+    path = "/api/v2/flashes/history",
+    operation_id = "v2_flash_history",
+    tag = "v2",
+    params(FlashHistoryParams),
+    responses(
+        (status = 200, body = ApiData<FlashHistoryPage>),
+        (status = 400, body = ApiErrorResponse),
+        (status = 500, body = ApiErrorResponse)
+    )
+)]
+async fn flash_history(
+    query: Result<Query<FlashHistoryParams>, QueryRejection>,
+    Extension(pool): Extension<Arc<SqlitePool>>,
+) -> ApiResult<FlashHistoryPage> {
+    let Query(params) = query.map_err(|e| ApiError::bad_request(rejection_message(&e)))?;
+
+    Ok(data(flash_history_impl(params, pool).await?))
+}
+
+/// Edit a message that hasn't been dismissed yet
+#[utoipa::path(
+    put,
+    path = "/api/v2/flashes/{id}",
+    operation_id = "v2_update_flash",
+    tag = "v2",
+    params(("id" = i64, Path, description = "ID of the message to edit")),
+    request_body(content(
+        (UpdateFlashParams = "application/json"),
+        (UpdateFlashParams = "application/x-www-form-urlencoded")
+    )),
+    responses(
+        (status = 200, body = ApiData<Flash>),
+        (status = 400, body = ApiErrorResponse),
+        (status = 404, body = ApiErrorResponse),
+        (status = 409, body = ApiErrorResponse),
+        (status = 500, body = ApiErrorResponse)
+    )
+)]
+async fn update_flash(
+    id: Result<Path<i64>, PathRejection>,
+    Extension(pool): Extension<Arc<SqlitePool>>,
+    Extension(config): Extension<Arc<Config>>,
+    Extension(events): Extension<Arc<EventBus>>,
+    FormOrJson(params): FormOrJson<UpdateFlashParams>,
+) -> ApiResult<Flash> {
+    let Path(id) = id.map_err(|e| ApiError::bad_request(rejection_message(&e)))?;
+
+    Ok(data(
+        update_flash_impl(id, params, pool, &config, &events).await?,
+    ))
+}
+
+/// Delete a message, along with its history
+#[utoipa::path(
+    delete,
+    path = "/api/v2/flashes/{id}",
+    operation_id = "v2_delete_flash",
+    tag = "v2",
+    params(("id" = i64, Path, description = "ID of the message to delete")),
+    responses(
+        (status = 200, body = ApiData<FlashId>),
+        (status = 400, body = ApiErrorResponse),
+        (status = 404, body = ApiErrorResponse),
+        (status = 500, body = ApiErrorResponse)
+    )
+)]
+async fn delete_flash(
+    id: Result<Path<i64>, PathRejection>,
+    Extension(pool): Extension<Arc<SqlitePool>>,
+    Extension(events): Extension<Arc<EventBus>>,
+) -> ApiResult<FlashId> {
+    let Path(id) = id.map_err(|e| ApiError::bad_request(rejection_message(&e)))?;
+    delete_flash_impl(id, pool, &events).await?;
+
+    Ok(data(FlashId { id }))
+}
+
+/// Dismiss a message
+#[utoipa::path(
+    post,
+    path = "/api/v2/flashes/dismiss",
+    operation_id = "v2_dismiss_flash",
+    tag = "v2",
+    request_body(content(
+        (DismissFlashParams = "application/json"),
+        (DismissFlashParams = "application/x-www-form-urlencoded")
+    )),
+    responses(
+        (status = 200, body = ApiData<FlashId>),
+        (status = 400, body = ApiErrorResponse),
+        (status = 403, body = ApiErrorResponse),
+        (status = 404, body = ApiErrorResponse),
+        (status = 409, body = ApiErrorResponse),
+        (status = 500, body = ApiErrorResponse)
+    )
+)]
+async fn dismiss_flash(
+    Extension(pool): Extension<Arc<SqlitePool>>,
+    Extension(events): Extension<Arc<EventBus>>,
+    FormOrJson(params): FormOrJson<DismissFlashParams>,
+) -> ApiResult<FlashId> {
+    let id = params.id;
+    dismiss_flash_impl(params, pool, &events).await?;
+
+    Ok(data(FlashId { id }))
+}
+
+/// Current weather at each configured station
+#[utoipa::path(
+    get,
+    path = "/api/v2/metars",
+    operation_id = "v2_get_metars",
+    tag = "v2",
+    responses(
+        (status = 200, body = ApiData<HashMap<String, StationMetar>>)
+    )
+)]
+async fn get_metars(
+    Extension(pool): Extension<Arc<SqlitePool>>,
+    Extension(config): Extension<Arc<Config>>,
+) -> ApiResult<HashMap<String, StationMetar>> {
+    Ok(data(cached_metars(&pool, &config).await?))
+}
+
+/// Report on chores caught up with at startup, if the server had been down
+#[utoipa::path(
+    get,
+    path = "/api/v2/catch_up",
+    operation_id = "v2_get_catch_up",
+    tag = "v2",
+    responses(
+        (status = 200, body = ApiData<Option<CatchUpReport>>)
+    )
+)]
+async fn get_catch_up(
+    Extension(catch_up_report): Extension<Arc<RwLock<Option<CatchUpReport>>>>,
+) -> ApiResult<Option<CatchUpReport>> {
+    Ok(data(catch_up_report.read().await.clone()))
+}
+
+/// Fallback for the whole server, as nested routers can't have their own
+pub async fn not_found(uri: Uri) -> Response {
+    match uri.path().starts_with("/api/v2/") {
+        true => ApiError::not_found(format!("no such endpoint {}", uri.path())).into_response(),
+        false => StatusCode::NOT_FOUND.into_response(),
+    }
+}
+
+/// Routes nested under `/api/v2`, which report errors with an `ApiErrorResponse` and a matching
+/// status code rather than `success: false`
+pub fn routes() -> Router {
+    Router::new()
+        .route("/chores", get(list_chores))
+        .route("/chores/archived", get(list_archived_chores))
+        .route("/chores/complete", post(complete_chore))
+        .route("/flashes", get(list_flashes).post(add_flash))
+        .route("/flashes/dismiss", post(dismiss_flash))
+        .route("/flashes/history", get(flash_history))
+        .route("/flashes/:id", put(update_flash).delete(delete_flash))
+        .route("/metars", get(get_metars))
+        .route("/catch_up", get(get_catch_up))
+}
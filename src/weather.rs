@@ -1,143 +1,589 @@
 use std::collections::HashMap;
 
 use anyhow::{anyhow, Result};
+use chrono::{Datelike, Duration, LocalResult, TimeZone, Utc};
 use futures::future::join_all;
-use metar::{Metar, Pressure, WeatherCondition, WeatherIntensity};
-use serde::Serialize;
+use metar::{Metar, Pressure, VertVisibility, Visibility, WeatherCondition, WeatherIntensity};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::weather_source::{MetarStation, Report, WeatherSource, WeatherSourceConfig};
 
 #[derive(Debug)]
 pub struct Observation {
     pub metar: String,
     pub interpretation: Metar,
+    /// When the source says the observation was made, which is more precise than the report
+    pub observed_at: Option<i64>,
 }
 
-async fn get_metar(station: &str) -> Result<Observation> {
-    const METAR_URL_BASE: &'static str =
-        "https://tgftp.nws.noaa.gov/data/observations/metar/stations/";
+impl Observation {
+    pub fn parse(report: Report) -> Result<Self> {
+        // stations send NIL in place of a report they couldn't make
+        if report.metar.split_whitespace().any(|token| token == "NIL") {
+            return Err(anyhow!("No observation in NIL report \"{}\"", report.metar));
+        }
 
-    let metar_text = reqwest::get(format!("{}{}.TXT", METAR_URL_BASE, station.to_uppercase()))
-        .await?
-        .text()
-        .await?;
+        // the parser's own message spans several lines to point at the problem
+        let interpretation = Metar::parse(&report.metar).map_err(|e| {
+            anyhow!(
+                "Invalid METAR \"{}\" at character {}",
+                report.metar,
+                e.start + 1
+            )
+        })?;
 
-    let mut lines: Vec<&str> = metar_text.lines().collect();
-    if lines.len() < 1 {
-        return Err(anyhow!("Too few lines returned in METAR for {}", station));
+        Ok(Observation {
+            metar: report.metar,
+            interpretation,
+            observed_at: report.observed_at,
+        })
     }
+}
 
-    let metar_line = lines.remove(1);
-
-    Ok(Observation {
-        metar: metar_line.to_string(),
-        interpretation: Metar::parse(metar_line)?,
-    })
+/// Fetches a station's latest report from `source` and interprets it
+async fn get_metar(station: &str, source: &dyn WeatherSource) -> Result<Observation> {
+    Observation::parse(source.fetch_report(station).await?)
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
 pub struct StationMetar {
     pub metar: String,
     pub pressure: Option<u16>,
     pub temperature: Option<i32>,
     pub weather: Vec<Weather>,
+    /// When the station made the observation
+    #[serde(default)]
+    pub observed_at: Option<i64>,
+    /// When the observation was last fetched from the station
+    #[serde(default)]
+    pub fetched_at: Option<i64>,
+    /// Whether the observation is older than the server's `metar_stale_after`, such as when the
+    /// station hasn't been reachable for a while
+    #[serde(default)]
+    #[schema(required = true)]
+    pub stale: bool,
+    /// Degrees Celsius, like `temperature`
+    #[serde(default)]
+    pub dewpoint: Option<i32>,
+    #[serde(default)]
+    pub wind: Option<Wind>,
+    #[serde(default)]
+    pub visibility_m: Option<u32>,
+    #[serde(default)]
+    pub visibility_sm: Option<f32>,
+    /// Whether the station reported CAVOK: at least 10 km of visibility, no cloud below 5000 ft
+    /// and no significant weather
+    #[serde(default)]
+    #[schema(required = true)]
+    pub cavok: bool,
+    /// From the lowest layer up
+    #[serde(default)]
+    #[schema(required = true)]
+    pub cloud_layers: Vec<CloudLayer>,
+    /// How far up can be seen through an obscured sky, which counts as the ceiling. Zero when the
+    /// station couldn't tell
+    #[serde(default)]
+    pub vertical_visibility_ft: Option<u32>,
+    #[serde(default)]
+    pub flight_category: Option<FlightCategory>,
+    /// The weather in a line, such as "Light rain, 4°C, wind NW 12 gusting 20 kt"
+    #[serde(default)]
+    #[schema(required = true)]
+    pub summary: String,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
 pub struct Weather {
     pub conditions: Vec<String>,
     pub intensity: String,
 }
 
-fn inhg_to_hectopascals(value: f32) -> u16 {
-    (value * 33.8639).trunc() as u16
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct Wind {
+    /// Degrees true the wind is blowing from, or `None` when it's calm or variable
+    pub direction: Option<u32>,
+    pub variable: bool,
+    pub speed_kt: u32,
+    pub gust_kt: Option<u32>,
+    /// The range of directions the wind is varying between, clockwise
+    pub varying_from: Option<u32>,
+    pub varying_to: Option<u32>,
 }
 
-pub async fn build_metar_response(stations: &[String]) -> HashMap<String, StationMetar> {
-    let mut futures = Vec::new();
-    for station in stations {
-        futures.push(get_metar(station));
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct CloudLayer {
+    /// `few`, `scattered`, `broken`, `overcast` or `unknown`
+    pub cover: String,
+    /// Height of the layer above the ground, when known
+    pub base_ft: Option<u32>,
+    /// `cumulonimbus` or `towering cumulus`, when the station calls them out
+    pub cloud_type: Option<String>,
+}
+
+/// The FAA's flight rules categories, from the ceiling and visibility
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum FlightCategory {
+    /// Ceiling above 3000 ft and visibility over 5 mi
+    Vfr,
+    /// Ceiling 1000 to 3000 ft or visibility 3 to 5 mi
+    Mvfr,
+    /// Ceiling 500 to under 1000 ft or visibility 1 to under 3 mi
+    Ifr,
+    /// Ceiling under 500 ft or visibility under 1 mi
+    Lifr,
+}
+
+impl FlightCategory {
+    pub fn name(self) -> &'static str {
+        match self {
+            FlightCategory::Vfr => "VFR",
+            FlightCategory::Mvfr => "MVFR",
+            FlightCategory::Ifr => "IFR",
+            FlightCategory::Lifr => "LIFR",
+        }
     }
 
-    let mut stations = HashMap::new();
-    for result in join_all(futures).await {
-        let metar = match result {
-            Ok(metar) => metar,
-            Err(e) => {
-                tracing::warn!("Failed to fetch METAR for a station: {}", e);
-                continue;
-            }
+    /// The category for a ceiling and visibility, where no ceiling means no broken or overcast
+    /// layer. `None` if the visibility wasn't reported
+    pub fn classify(ceiling_ft: Option<u32>, visibility_sm: Option<f32>) -> Option<Self> {
+        let visibility_sm = visibility_sm?;
+        let ceiling_ft = ceiling_ft.unwrap_or(u32::MAX);
+
+        Some(if ceiling_ft < 500 || visibility_sm < 1.0 {
+            FlightCategory::Lifr
+        } else if ceiling_ft < 1000 || visibility_sm < 3.0 {
+            FlightCategory::Ifr
+        } else if ceiling_ft <= 3000 || visibility_sm <= 5.0 {
+            FlightCategory::Mvfr
+        } else {
+            FlightCategory::Vfr
+        })
+    }
+}
+
+const METRES_PER_STATUTE_MILE: f32 = 1609.344;
+
+/// CAVOK means at least this much visibility
+const CAVOK_METRES: u32 = 10000;
+
+/// Cloud heights and vertical visibility are reported in hundreds of feet
+const FEET_PER_CLOUD_HEIGHT: u32 = 100;
+
+fn wind_speed_kt(speed: &metar::WindSpeed) -> u32 {
+    match *speed {
+        metar::WindSpeed::Calm => 0,
+        metar::WindSpeed::Knot(v) => v,
+        metar::WindSpeed::MetresPerSecond(v) => (v as f32 * 1.94384).round() as u32,
+        metar::WindSpeed::KilometresPerHour(v) => (v as f32 * 0.539957).round() as u32,
+    }
+}
+
+fn decode_wind(wind: &metar::Wind) -> Option<Wind> {
+    let speed_kt = match &wind.speed {
+        metar::Data::Known(speed) => wind_speed_kt(speed),
+        metar::Data::Unknown => return None,
+    };
+
+    let (direction, variable) = match wind.dir {
+        metar::Data::Known(metar::WindDirection::Heading(heading)) if speed_kt > 0 => {
+            (Some(heading), false)
+        }
+        metar::Data::Known(metar::WindDirection::Variable) => (None, true),
+        _ => (None, false),
+    };
+
+    Some(Wind {
+        direction,
+        variable,
+        speed_kt,
+        gust_kt: wind.gusting.as_ref().map(wind_speed_kt),
+        varying_from: wind.varying.map(|(from, _)| from),
+        varying_to: wind.varying.map(|(_, to)| to),
+    })
+}
+
+fn decode_cloud_layer(layer: &metar::CloudLayer) -> CloudLayer {
+    let (cover, cloud_type, base) = match layer {
+        metar::CloudLayer::Few(cloud_type, base) => ("few", cloud_type, base),
+        metar::CloudLayer::Scattered(cloud_type, base) => ("scattered", cloud_type, base),
+        metar::CloudLayer::Broken(cloud_type, base) => ("broken", cloud_type, base),
+        metar::CloudLayer::Overcast(cloud_type, base) => ("overcast", cloud_type, base),
+        metar::CloudLayer::Unknown(cloud_type, base) => ("unknown", cloud_type, base),
+    };
+
+    CloudLayer {
+        cover: cover.to_string(),
+        base_ft: base.map(|base| base * FEET_PER_CLOUD_HEIGHT),
+        cloud_type: match cloud_type {
+            metar::CloudType::Cumulonimbus => Some("cumulonimbus".to_string()),
+            metar::CloudType::ToweringCumulus => Some("towering cumulus".to_string()),
+            metar::CloudType::Normal | metar::CloudType::Unknown => None,
+        },
+    }
+}
+
+/// Height of the lowest broken or overcast layer, or of the vertical visibility
+fn ceiling_ft(cloud_layers: &[CloudLayer], vertical_visibility_ft: Option<u32>) -> Option<u32> {
+    cloud_layers
+        .iter()
+        .filter(|layer| layer.cover == "broken" || layer.cover == "overcast")
+        .filter_map(|layer| layer.base_ft)
+        .chain(vertical_visibility_ft)
+        .min()
+}
+
+/// Nearest of the eight compass points to a heading
+fn compass_point(heading: u32) -> &'static str {
+    const POINTS: [&str; 8] = ["N", "NE", "E", "SE", "S", "SW", "W", "NW"];
+    POINTS[((heading as f32 / 45.0).round() as usize) % POINTS.len()]
+}
+
+fn describe_weather(weather: &Weather) -> String {
+    let conditions = weather.conditions.join(" ");
+    match weather.intensity.as_str() {
+        "moderate" => conditions,
+        "in vicinity" => format!("{} nearby", conditions),
+        intensity => format!("{} {}", intensity, conditions),
+    }
+}
+
+fn describe_sky(metar: &StationMetar) -> &'static str {
+    if metar.vertical_visibility_ft.is_some() {
+        return "Sky obscured";
+    }
+
+    let covers = ["overcast", "broken", "scattered", "few"];
+    let cover = covers
+        .into_iter()
+        .find(|cover| metar.cloud_layers.iter().any(|layer| layer.cover == *cover));
+    match cover {
+        Some("overcast") => "Overcast",
+        Some("broken") => "Mostly cloudy",
+        Some("scattered") => "Partly cloudy",
+        Some(_) => "Mostly clear",
+        None => "Clear",
+    }
+}
+
+/// Describes the weather in a line, such as "Light rain, 4°C, wind NW 12 gusting 20 kt"
+fn summarize(metar: &StationMetar) -> String {
+    let mut parts = Vec::new();
+
+    let weather: Vec<String> = metar.weather.iter().map(describe_weather).collect();
+    let conditions = match weather.is_empty() {
+        true => describe_sky(metar).to_string(),
+        false => weather.join(" and "),
+    };
+    let mut chars = conditions.chars();
+    if let Some(first) = chars.next() {
+        parts.push(first.to_uppercase().chain(chars).collect::<String>());
+    }
+
+    if let Some(temperature) = metar.temperature {
+        parts.push(format!("{}\u{b0}C", temperature));
+    }
+
+    if let Some(wind) = &metar.wind {
+        let mut description = match (wind.speed_kt, wind.direction) {
+            (0, _) => "wind calm".to_string(),
+            (speed, Some(direction)) => format!("wind {} {}", compass_point(direction), speed),
+            (speed, None) => format!("wind variable {}", speed),
         };
+        if wind.speed_kt > 0 {
+            if let Some(gust) = wind.gust_kt {
+                description.push_str(&format!(" gusting {}", gust));
+            }
+            description.push_str(" kt");
+        }
+        parts.push(description);
+    }
 
-        let mut weather_response = Vec::new();
-        for weather in metar.interpretation.weather {
-            let mut conditions = Vec::new();
-            for condition in weather.conditions {
-                conditions.push(match condition {
-                    WeatherCondition::Shallow => "shallow".to_string(),
-                    WeatherCondition::Partial => "partial".to_string(),
-                    WeatherCondition::Patches => "patches".to_string(),
-                    WeatherCondition::LowDrifting => "low drifting".to_string(),
-                    WeatherCondition::Blowing => "blowing".to_string(),
-                    WeatherCondition::Showers => "showers".to_string(),
-                    WeatherCondition::Thunderstorm => "thunderstorm".to_string(),
-                    WeatherCondition::Freezing => "freezing".to_string(),
-                    WeatherCondition::Rain => "rain".to_string(),
-                    WeatherCondition::Drizzle => "drizzle".to_string(),
-                    WeatherCondition::Snow => "snow".to_string(),
-                    WeatherCondition::SnowGrains => "snow grains".to_string(),
-                    WeatherCondition::IceCrystals => "ice crystals".to_string(),
-                    WeatherCondition::IcePellets => "ice pellets".to_string(),
-                    WeatherCondition::Hail => "hail".to_string(),
-                    WeatherCondition::SnowPelletsOrSmallHail => {
-                        "snow pellets or small hail".to_string()
-                    }
-                    WeatherCondition::UnknownPrecipitation => "unknown precipitation".to_string(),
-                    WeatherCondition::Fog => "fog".to_string(),
-                    WeatherCondition::VolcanicAsh => "volcanic ash".to_string(),
-                    WeatherCondition::Mist => "mist".to_string(),
-                    WeatherCondition::Haze => "haze".to_string(),
-                    WeatherCondition::WidespreadDust => "widespread dust".to_string(),
-                    WeatherCondition::Smoke => "smoke".to_string(),
-                    WeatherCondition::Sand => "sand".to_string(),
-                    WeatherCondition::Spray => "spray".to_string(),
-                    WeatherCondition::Squall => "squall".to_string(),
-                    WeatherCondition::Dust => "dust".to_string(),
-                    WeatherCondition::Duststorm => "duststorm".to_string(),
-                    WeatherCondition::Sandstorm => "sandstorm".to_string(),
-                    WeatherCondition::FunnelCloud => "funnel cloud".to_string(),
-                });
+    parts.join(", ")
+}
+
+fn inhg_to_hectopascals(value: f32) -> u16 {
+    (value * 33.8639).round() as u16
+}
+
+/// Works out when an observation was made from the day of the month, hour and minute in its
+/// report, as the latest such time that isn't after it was fetched
+pub fn observation_time(time: &metar::Time, fetched_at: i64) -> Option<i64> {
+    // stations' clocks can run a little ahead of ours
+    let latest = Utc.timestamp_opt(fetched_at, 0).single()? + Duration::hours(1);
+
+    let (mut year, mut month) = (latest.year(), latest.month());
+    for _ in 0..2 {
+        let candidate = Utc.with_ymd_and_hms(
+            year,
+            month,
+            time.date.into(),
+            time.hour.into(),
+            time.minute.into(),
+            0,
+        );
+        if let LocalResult::Single(candidate) = candidate {
+            if candidate <= latest {
+                return Some(candidate.timestamp());
             }
+        }
+
+        (year, month) = match month {
+            1 => (year - 1, 12),
+            _ => (year, month - 1),
+        };
+    }
+
+    None
+}
+
+/// Decodes an observation that was fetched at `fetched_at`
+pub fn decode_observation(observation: Observation, fetched_at: i64) -> StationMetar {
+    let interpretation = observation.interpretation;
 
-            weather_response.push(Weather {
-                conditions,
-                intensity: match weather.intensity {
-                    WeatherIntensity::Light => "light".to_string(),
-                    WeatherIntensity::Moderate => "moderate".to_string(),
-                    WeatherIntensity::Heavy => "heavy".to_string(),
-                    WeatherIntensity::InVicinity => "in vicinity".to_string(),
-                    WeatherIntensity::Recent => "recent".to_string(),
-                },
+    let mut weather_response = Vec::new();
+    for weather in interpretation.weather {
+        let mut conditions = Vec::new();
+        for condition in weather.conditions {
+            conditions.push(match condition {
+                WeatherCondition::Shallow => "shallow".to_string(),
+                WeatherCondition::Partial => "partial".to_string(),
+                WeatherCondition::Patches => "patches".to_string(),
+                WeatherCondition::LowDrifting => "low drifting".to_string(),
+                WeatherCondition::Blowing => "blowing".to_string(),
+                WeatherCondition::Showers => "showers".to_string(),
+                WeatherCondition::Thunderstorm => "thunderstorm".to_string(),
+                WeatherCondition::Freezing => "freezing".to_string(),
+                WeatherCondition::Rain => "rain".to_string(),
+                WeatherCondition::Drizzle => "drizzle".to_string(),
+                WeatherCondition::Snow => "snow".to_string(),
+                WeatherCondition::SnowGrains => "snow grains".to_string(),
+                WeatherCondition::IceCrystals => "ice crystals".to_string(),
+                WeatherCondition::IcePellets => "ice pellets".to_string(),
+                WeatherCondition::Hail => "hail".to_string(),
+                WeatherCondition::SnowPelletsOrSmallHail => {
+                    "snow pellets or small hail".to_string()
+                }
+                WeatherCondition::UnknownPrecipitation => "unknown precipitation".to_string(),
+                WeatherCondition::Fog => "fog".to_string(),
+                WeatherCondition::VolcanicAsh => "volcanic ash".to_string(),
+                WeatherCondition::Mist => "mist".to_string(),
+                WeatherCondition::Haze => "haze".to_string(),
+                WeatherCondition::WidespreadDust => "widespread dust".to_string(),
+                WeatherCondition::Smoke => "smoke".to_string(),
+                WeatherCondition::Sand => "sand".to_string(),
+                WeatherCondition::Spray => "spray".to_string(),
+                WeatherCondition::Squall => "squall".to_string(),
+                WeatherCondition::Dust => "dust".to_string(),
+                WeatherCondition::Duststorm => "duststorm".to_string(),
+                WeatherCondition::Sandstorm => "sandstorm".to_string(),
+                WeatherCondition::FunnelCloud => "funnel cloud".to_string(),
             });
         }
 
-        stations.insert(
-            metar.interpretation.station,
-            StationMetar {
-                metar: metar.metar,
-                pressure: match metar.interpretation.pressure {
-                    metar::Data::Known(pressure) => match pressure {
-                        Pressure::Hectopascals(v) => Some(v),
-                        Pressure::InchesOfMercury(v) => Some(inhg_to_hectopascals(v)),
-                    },
-                    metar::Data::Unknown => None,
-                },
-                temperature: match metar.interpretation.temperature {
-                    metar::Data::Known(temp) => Some(temp),
-                    metar::Data::Unknown => None,
-                },
-                weather: weather_response,
+        weather_response.push(Weather {
+            conditions,
+            intensity: match weather.intensity {
+                WeatherIntensity::Light => "light".to_string(),
+                WeatherIntensity::Moderate => "moderate".to_string(),
+                WeatherIntensity::Heavy => "heavy".to_string(),
+                WeatherIntensity::InVicinity => "in vicinity".to_string(),
+                WeatherIntensity::Recent => "recent".to_string(),
             },
+        });
+    }
+
+    let (visibility_m, visibility_sm, cavok) = match interpretation.visibility {
+        metar::Data::Known(Visibility::CAVOK) => (
+            Some(CAVOK_METRES),
+            Some(CAVOK_METRES as f32 / METRES_PER_STATUTE_MILE),
+            true,
+        ),
+        metar::Data::Known(Visibility::Metres(v)) => (
+            Some(v.into()),
+            Some(v as f32 / METRES_PER_STATUTE_MILE),
+            false,
+        ),
+        metar::Data::Known(Visibility::StatuteMiles(v)) => (
+            Some((v * METRES_PER_STATUTE_MILE).round() as u32),
+            Some(v),
+            false,
+        ),
+        metar::Data::Unknown => (None, None, false),
+    };
+
+    let cloud_layers: Vec<CloudLayer> = interpretation
+        .cloud_layers
+        .iter()
+        .map(decode_cloud_layer)
+        .collect();
+    let vertical_visibility_ft =
+        interpretation
+            .vert_visibility
+            .map(|vertical_visibility| match vertical_visibility {
+                VertVisibility::Distance(v) => v * FEET_PER_CLOUD_HEIGHT,
+                VertVisibility::ReducedByUnknownAmount => 0,
+            });
+    let flight_category = FlightCategory::classify(
+        ceiling_ft(&cloud_layers, vertical_visibility_ft),
+        visibility_sm,
+    );
+
+    let mut metar = StationMetar {
+        metar: observation.metar,
+        pressure: match interpretation.pressure {
+            metar::Data::Known(pressure) => match pressure {
+                Pressure::Hectopascals(v) => Some(v),
+                Pressure::InchesOfMercury(v) => Some(inhg_to_hectopascals(v)),
+            },
+            metar::Data::Unknown => None,
+        },
+        temperature: match interpretation.temperature {
+            metar::Data::Known(temp) => Some(temp),
+            metar::Data::Unknown => None,
+        },
+        weather: weather_response,
+        observed_at: observation
+            .observed_at
+            .or_else(|| observation_time(&interpretation.time, fetched_at)),
+        fetched_at: Some(fetched_at),
+        stale: false,
+        dewpoint: match interpretation.dewpoint {
+            metar::Data::Known(dewpoint) => Some(dewpoint),
+            metar::Data::Unknown => None,
+        },
+        wind: decode_wind(&interpretation.wind),
+        visibility_m,
+        visibility_sm,
+        cavok,
+        cloud_layers,
+        vertical_visibility_ft,
+        flight_category,
+        summary: String::new(),
+    };
+    metar.summary = summarize(&metar);
+
+    metar
+}
+
+/// Fetches and decodes the latest observation at each station, from its own source or else
+/// `default_source`, along with why each station that couldn't be fetched failed
+pub async fn fetch_metars(
+    stations: &[MetarStation],
+    default_source: &WeatherSourceConfig,
+) -> (HashMap<String, StationMetar>, HashMap<String, String>) {
+    let mut futures = Vec::new();
+    for station in stations {
+        futures.push(get_metar(station.id(), station.source(default_source)));
+    }
+    let results = join_all(futures).await;
+    let fetched_at = Utc::now().timestamp();
+
+    let mut failures = HashMap::new();
+    let mut metars = HashMap::new();
+    for (station, result) in stations.iter().zip(results) {
+        match result {
+            Ok(observation) => {
+                let metar = decode_observation(observation, fetched_at);
+                metars.insert(station.id().to_string(), metar);
+            }
+            Err(e) => {
+                tracing::warn!("Failed to fetch METAR for {}: {}", station.id(), e);
+                failures.insert(station.id().to_string(), e.to_string());
+            }
+        }
+    }
+
+    (metars, failures)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 2026-10-18 18:00 UTC
+    const FETCHED_AT: i64 = 1792346400;
+
+    fn decode(report: &str) -> StationMetar {
+        let observation = Observation::parse(Report {
+            metar: report.to_string(),
+            observed_at: None,
+        })
+        .unwrap();
+
+        decode_observation(observation, FETCHED_AT)
+    }
+
+    #[test]
+    fn decodes_every_field() {
+        let metar = decode("KBOS 181754Z 31012G20KT 280V340 4SM -RA BR FEW008 OVC015 04/02 A2990");
+
+        assert_eq!(metar.temperature, Some(4));
+        assert_eq!(metar.dewpoint, Some(2));
+        assert_eq!(metar.pressure, Some(1013));
+        assert_eq!(metar.observed_at, Some(FETCHED_AT - 6 * 60));
+
+        let wind = metar.wind.unwrap();
+        assert_eq!(wind.direction, Some(310));
+        assert_eq!(wind.speed_kt, 12);
+        assert_eq!(wind.gust_kt, Some(20));
+        assert_eq!((wind.varying_from, wind.varying_to), (Some(280), Some(340)));
+
+        assert_eq!(metar.visibility_sm, Some(4.0));
+        assert_eq!(metar.visibility_m, Some(6437));
+        let bases: Vec<_> = metar
+            .cloud_layers
+            .iter()
+            .map(|layer| layer.base_ft)
+            .collect();
+        assert_eq!(bases, [Some(800), Some(1500)]);
+        assert_eq!(metar.flight_category, Some(FlightCategory::Mvfr));
+
+        assert_eq!(
+            metar.summary,
+            "Light rain and mist, 4\u{b0}C, wind NW 12 gusting 20 kt"
         );
     }
 
-    stations
+    #[test]
+    fn rounds_pressure_to_the_nearest_hectopascal() {
+        assert_eq!(inhg_to_hectopascals(30.12), 1020);
+        assert_eq!(inhg_to_hectopascals(29.92), 1013);
+        assert_eq!(inhg_to_hectopascals(29.50), 999);
+    }
+
+    #[test]
+    fn summarizes_quiet_weather() {
+        let metar = decode("LFPG 181800Z 00000KT CAVOK 15/10 Q1020 NOSIG");
+        assert!(metar.cavok);
+        assert_eq!(metar.flight_category, Some(FlightCategory::Vfr));
+        assert_eq!(metar.summary, "Clear, 15\u{b0}C, wind calm");
+
+        let metar = decode("KSEA 181753Z VRB03KT 1/2SM FG VV002 11/11 A3001");
+        assert_eq!(metar.vertical_visibility_ft, Some(200));
+        assert_eq!(metar.flight_category, Some(FlightCategory::Lifr));
+        assert_eq!(metar.summary, "Fog, 11\u{b0}C, wind variable 3 kt");
+    }
+
+    #[test]
+    fn classifies_flight_categories() {
+        assert_eq!(
+            FlightCategory::classify(None, Some(10.0)),
+            Some(FlightCategory::Vfr)
+        );
+        assert_eq!(
+            FlightCategory::classify(Some(3000), Some(10.0)),
+            Some(FlightCategory::Mvfr)
+        );
+        assert_eq!(
+            FlightCategory::classify(Some(5000), Some(2.5)),
+            Some(FlightCategory::Ifr)
+        );
+        assert_eq!(
+            FlightCategory::classify(Some(900), Some(0.75)),
+            Some(FlightCategory::Lifr)
+        );
+        assert_eq!(FlightCategory::classify(Some(900), None), None);
+    }
 }
@@ -0,0 +1,558 @@
+use std::path::PathBuf;
+
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, NaiveDateTime};
+use futures::future::BoxFuture;
+use serde::Deserialize;
+
+const NOAA_URL: &str = "https://tgftp.nws.noaa.gov/data/observations/metar/stations/";
+const AVIATION_WEATHER_URL: &str = "https://aviationweather.gov/api/data/metar";
+
+/// Format of the timestamp on the first line of the NWS's text files, which is in UTC
+const NOAA_TIME_FORMAT: &str = "%Y/%m/%d %H:%M";
+
+/// A station's latest report as fetched, before it's interpreted
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Report {
+    /// The report's METAR text, without any `METAR`/`SPECI` prefix or maintenance flag
+    pub metar: String,
+    /// When the observation was made, if the source says so outside of the report itself
+    pub observed_at: Option<i64>,
+}
+
+/// Somewhere the latest METAR report for a station can be fetched from
+pub trait WeatherSource: Send + Sync {
+    fn fetch_report<'a>(&'a self, station: &'a str) -> BoxFuture<'a, Result<Report>>;
+}
+
+/// Tidies up a line of METAR text, returning `None` if it isn't a report for `station`.
+///
+/// Sources may put `METAR` or `SPECI` before the station, end the report with `=` and trailing
+/// whitespace, and add a `$` when the station needs maintenance, none of which the parser accepts
+pub fn clean_report(line: &str, station: &str) -> Option<String> {
+    let mut report = line.trim();
+    for prefix in ["METAR ", "SPECI "] {
+        if let Some(rest) = report.strip_prefix(prefix) {
+            report = rest.trim_start();
+        }
+    }
+
+    let mut tokens: Vec<&str> = report.split_whitespace().collect();
+    if let Some(last) = tokens.last_mut() {
+        *last = last.trim_end_matches('=');
+    }
+    tokens.retain(|token| !token.is_empty() && *token != "$");
+
+    match tokens.first() {
+        Some(id) if id.eq_ignore_ascii_case(station) => Some(tokens.join(" ")),
+        _ => None,
+    }
+}
+
+/// Finds the report for a station among lines that each hold one report
+fn find_report(text: &str, station: &str) -> Option<Report> {
+    text.lines()
+        .find_map(|line| clean_report(line, station))
+        .map(|metar| Report {
+            metar,
+            observed_at: None,
+        })
+}
+
+/// Parses the NWS's text file format, which is the time of the observation followed by the report
+/// on the next line, or the next few lines when it's long
+pub fn parse_noaa_text(text: &str, station: &str) -> Result<Report> {
+    let mut lines = text.lines().map(str::trim).filter(|line| !line.is_empty());
+
+    let first = lines
+        .next()
+        .ok_or_else(|| anyhow!("Empty METAR response for {}", station))?;
+    let observed_at = match NaiveDateTime::parse_from_str(first, NOAA_TIME_FORMAT) {
+        Ok(time) => time.and_utc().timestamp(),
+        // some mirrors leave the timestamp off
+        Err(_) => {
+            return find_report(first, station).ok_or_else(|| {
+                anyhow!(
+                    "Expected a timestamp or a METAR for {}, got \"{}\"",
+                    station,
+                    first
+                )
+            })
+        }
+    };
+
+    // long reports wrap onto indented continuation lines
+    let report = lines.collect::<Vec<_>>().join(" ");
+    if report.is_empty() {
+        return Err(anyhow!("No METAR for {} after the timestamp", station));
+    }
+    let metar = clean_report(&report, station)
+        .ok_or_else(|| anyhow!("Expected a METAR for {}, got \"{}\"", station, report))?;
+
+    Ok(Report {
+        metar,
+        observed_at: Some(observed_at),
+    })
+}
+
+async fn get_text(url: &str) -> Result<String> {
+    Ok(reqwest::get(url).await?.error_for_status()?.text().await?)
+}
+
+fn noaa_url() -> String {
+    NOAA_URL.to_string()
+}
+
+fn aviation_weather_url() -> String {
+    AVIATION_WEATHER_URL.to_string()
+}
+
+/// The NWS's text files of the latest report at each station, which are a timestamp followed by the
+/// report
+#[derive(Deserialize, Debug, Clone)]
+pub struct NoaaSource {
+    /// Directory the `<STATION>.TXT` files are under
+    #[serde(default = "noaa_url")]
+    pub url: String,
+}
+
+impl Default for NoaaSource {
+    fn default() -> Self {
+        NoaaSource { url: noaa_url() }
+    }
+}
+
+impl WeatherSource for NoaaSource {
+    fn fetch_report<'a>(&'a self, station: &'a str) -> BoxFuture<'a, Result<Report>> {
+        Box::pin(async move {
+            let url = format!(
+                "{}/{}.TXT",
+                self.url.trim_end_matches('/'),
+                station.to_uppercase()
+            );
+
+            parse_noaa_text(&get_text(&url).await?, station)
+        })
+    }
+}
+
+#[derive(Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum AviationWeatherFormat {
+    #[default]
+    Json,
+    Xml,
+}
+
+/// The Aviation Weather Center's data API, which takes the station as the `ids` parameter
+#[derive(Deserialize, Debug, Clone)]
+pub struct AviationWeatherSource {
+    #[serde(default = "aviation_weather_url")]
+    pub url: String,
+    #[serde(default)]
+    pub format: AviationWeatherFormat,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AviationWeatherReport {
+    raw_ob: String,
+    obs_time: Option<i64>,
+}
+
+/// The contents of each `<tag>` element in `body`, which is all that's needed of the API's XML
+fn xml_elements<'a>(body: &'a str, tag: &str) -> Result<Vec<&'a str>> {
+    let open = format!("<{}>", tag);
+    let close = format!("</{}>", tag);
+
+    let mut elements = Vec::new();
+    let mut rest = body;
+    while let Some(start) = rest.find(&open) {
+        rest = &rest[start + open.len()..];
+        let end = rest
+            .find(&close)
+            .ok_or_else(|| anyhow!("Unterminated {} in the response", open))?;
+
+        elements.push(&rest[..end]);
+        rest = &rest[end + close.len()..];
+    }
+
+    Ok(elements)
+}
+
+fn unescape_xml(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+impl AviationWeatherSource {
+    fn parse_json(body: &str, station: &str) -> Result<Report> {
+        let reports: Vec<AviationWeatherReport> = serde_json::from_str(body)?;
+
+        reports
+            .iter()
+            .find_map(|report| {
+                Some(Report {
+                    metar: clean_report(&report.raw_ob, station)?,
+                    observed_at: report.obs_time,
+                })
+            })
+            .ok_or_else(|| anyhow!("No METAR for {} in the response", station))
+    }
+
+    fn parse_xml(body: &str, station: &str) -> Result<Report> {
+        for element in xml_elements(body, "METAR")? {
+            let metar = match xml_elements(element, "raw_text")?.first() {
+                Some(raw_text) => clean_report(&unescape_xml(raw_text), station),
+                None => None,
+            };
+            let metar = match metar {
+                Some(metar) => metar,
+                None => continue,
+            };
+
+            let observed_at = xml_elements(element, "observation_time")?
+                .first()
+                .and_then(|time| DateTime::parse_from_rfc3339(time.trim()).ok())
+                .map(|time| time.timestamp());
+
+            return Ok(Report { metar, observed_at });
+        }
+
+        Err(anyhow!("No METAR for {} in the response", station))
+    }
+}
+
+impl WeatherSource for AviationWeatherSource {
+    fn fetch_report<'a>(&'a self, station: &'a str) -> BoxFuture<'a, Result<Report>> {
+        Box::pin(async move {
+            let format = match self.format {
+                AviationWeatherFormat::Json => "json",
+                AviationWeatherFormat::Xml => "xml",
+            };
+            let url = format!(
+                "{}?ids={}&format={}",
+                self.url,
+                station.to_uppercase(),
+                format
+            );
+            let body = get_text(&url).await?;
+
+            match self.format {
+                AviationWeatherFormat::Json => Self::parse_json(&body, station),
+                AviationWeatherFormat::Xml => Self::parse_xml(&body, station),
+            }
+        })
+    }
+}
+
+/// Reports on disk, either as a directory of `<STATION>.TXT` files like the NWS serves or as a
+/// single file with a line for each station
+#[derive(Deserialize, Debug, Clone)]
+pub struct FileSource {
+    pub path: PathBuf,
+}
+
+impl WeatherSource for FileSource {
+    fn fetch_report<'a>(&'a self, station: &'a str) -> BoxFuture<'a, Result<Report>> {
+        Box::pin(async move {
+            let is_dir = tokio::fs::metadata(&self.path).await?.is_dir();
+            let path = match is_dir {
+                true => self.path.join(format!("{}.TXT", station.to_uppercase())),
+                false => self.path.clone(),
+            };
+            let text = tokio::fs::read_to_string(&path)
+                .await
+                .map_err(|e| anyhow!("Failed to read {}: {}", path.display(), e))?;
+
+            match is_dir {
+                true => parse_noaa_text(&text, station),
+                false => find_report(&text, station)
+                    .ok_or_else(|| anyhow!("No METAR for {} in {}", station, path.display())),
+            }
+        })
+    }
+}
+
+/// Where to fetch a station's weather from, as configured
+#[derive(Deserialize, Debug, Clone)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum WeatherSourceConfig {
+    Noaa(NoaaSource),
+    AviationWeather(AviationWeatherSource),
+    File(FileSource),
+}
+
+impl Default for WeatherSourceConfig {
+    fn default() -> Self {
+        WeatherSourceConfig::Noaa(NoaaSource::default())
+    }
+}
+
+impl WeatherSourceConfig {
+    pub fn source(&self) -> &dyn WeatherSource {
+        match self {
+            WeatherSourceConfig::Noaa(source) => source,
+            WeatherSourceConfig::AviationWeather(source) => source,
+            WeatherSourceConfig::File(source) => source,
+        }
+    }
+}
+
+/// A station in `metar_stations`, which is either its ID, fetched from the default source, or an
+/// object naming the source to fetch it from
+#[derive(Deserialize, Debug, Clone)]
+#[serde(untagged)]
+pub enum MetarStation {
+    Id(String),
+    WithSource {
+        station: String,
+        source: WeatherSourceConfig,
+    },
+}
+
+impl MetarStation {
+    pub fn id(&self) -> &str {
+        match self {
+            MetarStation::Id(id) => id,
+            MetarStation::WithSource { station, .. } => station,
+        }
+    }
+
+    /// The station's own source, or `default` if it doesn't have one
+    pub fn source<'a>(&'a self, default: &'a WeatherSourceConfig) -> &'a dyn WeatherSource {
+        match self {
+            MetarStation::Id(_) => default.source(),
+            MetarStation::WithSource { source, .. } => source.source(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::SocketAddr;
+
+    use axum::extract::Query;
+    use axum::routing::get;
+    use axum::Router;
+    use serde::Deserialize;
+
+    use super::*;
+
+    const REPORT: &str = "KDEN 181753Z 17009KT 10SM FEW100 SCT200 22/M03 A3012";
+    /// 2026-10-18 17:53 UTC, when `REPORT` was observed
+    const OBSERVED_AT: i64 = 1792345980;
+
+    fn report(observed_at: Option<i64>) -> Report {
+        Report {
+            metar: REPORT.to_string(),
+            observed_at,
+        }
+    }
+
+    #[derive(Deserialize)]
+    struct AviationWeatherParams {
+        ids: String,
+        format: String,
+    }
+
+    async fn aviation_weather(Query(params): Query<AviationWeatherParams>) -> String {
+        assert_eq!(params.ids, "KDEN");
+        match params.format.as_str() {
+            "json" => format!(
+                r#"[{{"icaoId":"KDEN","obsTime":{},"rawOb":"METAR {}"}}]"#,
+                OBSERVED_AT, REPORT
+            ),
+            _ => format!(
+                "<response><data num_results=\"1\"><METAR><raw_text>{}</raw_text>\
+                 <station_id>KDEN</station_id>\
+                 <observation_time>2026-10-18T17:53:00Z</observation_time></METAR></data>\
+                 </response>",
+                REPORT
+            ),
+        }
+    }
+
+    /// Serves canned responses in place of the real sources, returning its address
+    async fn stand_in_server() -> SocketAddr {
+        let app = Router::new()
+            .route(
+                "/stations/KDEN.TXT",
+                get(|| async { format!("2026/10/18 17:53\n{}\n", REPORT) }),
+            )
+            .route("/stations/KBAD.TXT", get(|| async { "2026/10/18 17:53\n" }))
+            .route("/api/data/metar", get(aviation_weather));
+
+        let server = axum::Server::bind(&SocketAddr::from(([127, 0, 0, 1], 0)))
+            .serve(app.into_make_service());
+        let addr = server.local_addr();
+        tokio::spawn(server);
+
+        addr
+    }
+
+    #[tokio::test]
+    async fn noaa_source() {
+        let addr = stand_in_server().await;
+        let source = NoaaSource {
+            url: format!("http://{}/stations/", addr),
+        };
+
+        assert_eq!(
+            source.fetch_report("kden").await.unwrap(),
+            report(Some(OBSERVED_AT))
+        );
+        assert!(source.fetch_report("KBAD").await.is_err());
+        assert!(source.fetch_report("KMISSING").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn aviation_weather_source() {
+        let addr = stand_in_server().await;
+
+        for format in [AviationWeatherFormat::Json, AviationWeatherFormat::Xml] {
+            let source = AviationWeatherSource {
+                url: format!("http://{}/api/data/metar", addr),
+                format,
+            };
+
+            assert_eq!(
+                source.fetch_report("KDEN").await.unwrap(),
+                report(Some(OBSERVED_AT))
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn file_source() {
+        let dir = std::env::temp_dir().join(format!("chores-weather-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(
+            dir.join("KDEN.TXT"),
+            format!("2026/10/18 17:53\n{}\n", REPORT),
+        )
+        .unwrap();
+        let list = dir.join("reports.txt");
+        std::fs::write(&list, format!("KBJC 181750Z 00000KT\n{}\n", REPORT)).unwrap();
+
+        let directory = FileSource { path: dir.clone() };
+        assert_eq!(
+            directory.fetch_report("KDEN").await.unwrap(),
+            report(Some(OBSERVED_AT))
+        );
+        assert!(directory.fetch_report("KBJC").await.is_err());
+
+        let file = FileSource { path: list };
+        assert_eq!(file.fetch_report("KDEN").await.unwrap(), report(None));
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn station_config() {
+        let stations: Vec<MetarStation> = serde_json::from_str(
+            r#"["KDEN", {"station": "KBJC", "source": {"type": "aviation_weather", "format": "xml"}}]"#,
+        )
+        .unwrap();
+
+        assert_eq!(stations[0].id(), "KDEN");
+        assert!(matches!(stations[0], MetarStation::Id(_)));
+        match &stations[1] {
+            MetarStation::WithSource {
+                station,
+                source: WeatherSourceConfig::AviationWeather(source),
+            } => {
+                assert_eq!(station, "KBJC");
+                assert_eq!(source.url, AVIATION_WEATHER_URL);
+                assert_eq!(source.format, AviationWeatherFormat::Xml);
+            }
+            other => panic!("unexpected station {:?}", other),
+        }
+    }
+
+    /// Each file in `fixtures/metar` (or its `malformed` directory) is named for its station,
+    /// followed by what it covers when there's more than one for a station
+    fn fixtures(dir: &str) -> Vec<(String, String)> {
+        let dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join(dir);
+        let mut fixtures: Vec<_> = std::fs::read_dir(&dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .filter(|path| path.extension().is_some_and(|extension| extension == "TXT"))
+            .map(|path| {
+                let name = path.file_stem().unwrap().to_string_lossy().to_string();
+                let station = name.split('-').next().unwrap().to_string();
+                (station, std::fs::read_to_string(&path).unwrap())
+            })
+            .collect();
+        fixtures.sort();
+
+        fixtures
+    }
+
+    #[test]
+    fn noaa_fixtures() {
+        for (station, text) in fixtures("fixtures/metar") {
+            let report =
+                parse_noaa_text(&text, &station).unwrap_or_else(|e| panic!("{}: {}", station, e));
+
+            assert!(
+                report.metar.starts_with(&format!("{} ", station)),
+                "{}",
+                station
+            );
+            assert!(!report.metar.contains('$'), "{}", station);
+            assert!(!report.metar.ends_with('='), "{}", station);
+            assert!(report.observed_at.is_some(), "{}", station);
+            if let Err(e) = crate::weather::Observation::parse(report) {
+                panic!("{}: {}", station, e);
+            }
+        }
+    }
+
+    #[test]
+    fn malformed_noaa_fixtures() {
+        for (station, text) in fixtures("fixtures/metar/malformed") {
+            let parsed =
+                parse_noaa_text(&text, &station).and_then(crate::weather::Observation::parse);
+            assert!(parsed.is_err(), "{} parsed as {:?}", station, parsed);
+        }
+    }
+
+    #[test]
+    fn cleans_reports() {
+        assert_eq!(
+            clean_report("  SPECI KBJC 181756Z 00000KT A3015 RMK AO2 $  ", "KBJC").as_deref(),
+            Some("KBJC 181756Z 00000KT A3015 RMK AO2")
+        );
+        assert_eq!(
+            clean_report("METAR LFPG 181800Z CAVOK Q1020=", "lfpg").as_deref(),
+            Some("LFPG 181800Z CAVOK Q1020")
+        );
+        assert_eq!(clean_report("KDEN 181753Z", "KBJC"), None);
+        assert_eq!(clean_report("", "KBJC"), None);
+    }
+
+    #[test]
+    fn joins_wrapped_reports() {
+        let (_, text) = fixtures("fixtures/metar")
+            .into_iter()
+            .find(|(_, text)| text.contains("131253Z"))
+            .unwrap();
+        let report = parse_noaa_text(&text, "KLAX").unwrap();
+
+        assert!(report
+            .metar
+            .ends_with("SLP108 VIS N-NE 1 1/4 VIS S 2 1/2 DZB10 P0000 T01780167"));
+    }
+
+    #[test]
+    fn rejects_nil_reports() {
+        let report = parse_noaa_text("2026/10/06 18:53\nKLAX 061853Z NIL=\n", "KLAX").unwrap();
+        let error = crate::weather::Observation::parse(report).unwrap_err();
+
+        assert!(error.to_string().contains("NIL"), "{}", error);
+    }
+}